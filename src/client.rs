@@ -0,0 +1,173 @@
+use std::cmp::Ordering;
+
+use serde_json::Value;
+
+use crate::export::{self, CsvOptions};
+use crate::model::{ColumnFilter, ColumnFilterValue, FilterExpr, SortDirection, SortSpec, TableHeader};
+
+/// Keep only the rows where at least one header column contains the search string (case insensitive)
+pub fn search_rows(headers: &[TableHeader], rows: Vec<Value>, search: &str) -> Vec<Value> {
+    let search = search.trim().to_uppercase();
+    if search.is_empty() {
+        return rows;
+    }
+    rows.into_iter()
+        .filter(|row| {
+            headers
+                .iter()
+                .any(|header| header.display_value(row).to_uppercase().contains(&search))
+        })
+        .collect()
+}
+
+/// Keep only the rows matching every column filter, filters on unknown columns are skipped
+///
+/// The filters are evaluated as their `FilterExpr` on the `name` of the header, the same expression sent to the server,
+/// except the date ranges of dates parsed by the header (Unix timestamps, `DateFormat` patterns) which are compared as dates.
+pub fn filter_rows(headers: &[TableHeader], rows: Vec<Value>, filters: &[ColumnFilter]) -> Vec<Value> {
+    let filters = filters
+        .iter()
+        .filter_map(|filter| {
+            find_header(headers, &filter.field).map(|header| {
+                let expr = ColumnFilter {
+                    field: header.name.clone(),
+                    value: filter.value.clone(),
+                }
+                .to_expr();
+                (header, &filter.value, expr)
+            })
+        })
+        .collect::<Vec<_>>();
+    if filters.is_empty() {
+        return rows;
+    }
+    rows.into_iter()
+        .filter(|row| {
+            filters.iter().all(|(header, value, expr)| match value {
+                ColumnFilterValue::DateRange { from, to } => header
+                    .date_in_range(row, from.as_deref(), to.as_deref())
+                    .unwrap_or_else(|| expr.matches(row)),
+                _ => expr.matches(row),
+            })
+        })
+        .collect()
+}
+
+/// Keep only the rows matching the filter expression, every row when there is none
+pub fn condition_rows(rows: Vec<Value>, condition: &Option<FilterExpr>) -> Vec<Value> {
+    match condition {
+        Some(condition) => rows.into_iter().filter(|row| condition.matches(row)).collect(),
+        None => rows,
+    }
+}
+
+/// Sort the rows by each sorted column in order of priority
+///
+/// A sorted column matches the header whose `sort_name` (or `name`) is equal to its `sort_name`, unknown columns are skipped.
+/// Values are compared by the kind of the header (numbers, dates, booleans, enum options), see `ColumnKind::compare`.
+pub fn sort_rows(headers: &[TableHeader], rows: &mut [Value], sort: &[SortSpec]) {
+    let keys = sort
+        .iter()
+        .filter_map(|spec| find_header(headers, &spec.sort_name).map(|header| (header, spec.direction)))
+        .collect::<Vec<_>>();
+    if keys.is_empty() {
+        return;
+    }
+    rows.sort_by(|a, b| {
+        keys.iter()
+            .map(|(header, direction)| {
+                let ordering = header.compare(a, b);
+                match direction {
+                    SortDirection::Ascending => ordering,
+                    SortDirection::Descending => ordering.reverse(),
+                }
+            })
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(Ordering::Equal)
+    });
+}
+
+/// Slice the rows for the current page
+pub fn page_rows(rows: &[Value], offset: u32, limit: u32) -> Vec<Value> {
+    rows.iter()
+        .skip(offset as usize)
+        .take(limit as usize)
+        .cloned()
+        .collect()
+}
+
+/// The header whose `sort_name` (or `name`) is equal to the field
+fn find_header<'a>(headers: &'a [TableHeader], field: &str) -> Option<&'a TableHeader> {
+    headers
+        .iter()
+        .find(|h| h.sort_name == field)
+        .or_else(|| headers.iter().find(|h| h.name == field))
+}
+
+/// Build a plain header for every field found in the rows, in the order they first appear
+pub fn headers_from_rows(rows: &[Value]) -> Vec<TableHeader> {
+    let mut headers: Vec<TableHeader> = Vec::new();
+    for row in rows {
+        if let Value::Object(map) = row {
+            for key in map.keys() {
+                if !headers.iter().any(|h| &h.name == key) {
+                    headers.push(TableHeader {
+                        name: key.clone(),
+                        sort_name: key.clone(),
+                        display_name: key.clone(),
+                        ..Default::default()
+                    });
+                }
+            }
+        }
+    }
+    headers
+}
+
+/// Build a CSV content from the rows, using `display_name` as the header row, see `export::to_csv` for the options
+pub fn to_csv(headers: &[TableHeader], rows: &[Value]) -> String {
+    export::to_csv(headers, rows, &CsvOptions::default())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::format::{DateFormat, DateZone, UnixUnit};
+    use crate::model::ColumnKind;
+
+    #[test]
+    fn filter_rows_uses_the_filter_expression() {
+        let headers = vec![TableHeader::text("status"), TableHeader::text("profit").sortable_as("profit_amount")];
+        let rows = vec![
+            json!({"status": "Gain", "profit": 10}),
+            json!({"status": "gain", "profit": null}),
+            json!({"status": "Loss", "profit": 3}),
+        ];
+        let filter = |field: &str, value| ColumnFilter { field: field.to_string(), value };
+        let equals = filter_rows(&headers, rows.clone(), &[filter("status", ColumnFilterValue::Equals("Gain".to_string()))]);
+        assert_eq!(equals, vec![rows[0].clone()]);
+        let at_least = filter_rows(&headers, rows.clone(), &[filter("profit_amount", ColumnFilterValue::NumberRange { min: Some(5.0), max: None })]);
+        assert_eq!(at_least, vec![rows[0].clone()]);
+        let unknown = filter_rows(&headers, rows.clone(), &[filter("missing", ColumnFilterValue::Boolean(true))]);
+        assert_eq!(unknown, rows);
+    }
+
+    #[test]
+    fn filter_rows_parses_the_dates_of_a_date_range() {
+        let headers = vec![TableHeader::of_kind("closed_at", ColumnKind::Date).date_format(DateFormat::default().unix(UnixUnit::Seconds).zone(DateZone::Utc))];
+        let rows = vec![
+            json!({"closed_at": 1_709_251_200}), // 2024-03-01T00:00:00Z
+            json!({"closed_at": 1_709_337_599}), // 2024-03-01T23:59:59Z
+            json!({"closed_at": 1_709_337_600}), // 2024-03-02T00:00:00Z
+            json!({"closed_at": "not a date"}),
+        ];
+        let range = |from: &str, to: &str| ColumnFilter {
+            field: "closed_at".to_string(),
+            value: ColumnFilterValue::DateRange { from: Some(from.to_string()), to: Some(to.to_string()) },
+        };
+        assert_eq!(filter_rows(&headers, rows.clone(), &[range("2024-03-01", "2024-03-01")]), rows[..2].to_vec());
+        assert_eq!(filter_rows(&headers, rows.clone(), &[range("2024-03-02", "2024-12-31")]), vec![rows[2].clone()]);
+    }
+}
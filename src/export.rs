@@ -0,0 +1,453 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::model::{ColumnKind, TableHeader};
+use crate::xlsx;
+
+/// The format of an exported file
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    /// RFC 4180 comma separated values
+    #[default]
+    Csv,
+    /// Tab separated values, the tabs and line breaks of the values are replaced by spaces
+    Tsv,
+    /// An array of objects keyed by the `display_name` of the headers
+    Json,
+    /// An object per line keyed by the `display_name` of the headers
+    Ndjson,
+    /// An Excel workbook with numbers and dates as typed cells and a bold header row
+    Xlsx,
+}
+
+impl ExportFormat {
+    /// Every format, in the order of the format picker
+    pub fn all() -> Vec<ExportFormat> {
+        vec![ExportFormat::Csv, ExportFormat::Tsv, ExportFormat::Json, ExportFormat::Ndjson, ExportFormat::Xlsx]
+    }
+
+    /// The label of the format picker and the download button
+    pub fn label(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "CSV",
+            ExportFormat::Tsv => "TSV",
+            ExportFormat::Json => "JSON",
+            ExportFormat::Ndjson => "NDJSON",
+            ExportFormat::Xlsx => "XLSX",
+        }
+    }
+
+    /// Whether the file is binary, a binary file produced by a back end is base64 encoded
+    pub fn is_binary(&self) -> bool {
+        *self == ExportFormat::Xlsx
+    }
+
+    pub fn mime(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "text/csv",
+            ExportFormat::Tsv => "text/tab-separated-values",
+            ExportFormat::Json => "application/json",
+            ExportFormat::Ndjson => "application/x-ndjson",
+            ExportFormat::Xlsx => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Tsv => "tsv",
+            ExportFormat::Json => "json",
+            ExportFormat::Ndjson => "ndjson",
+            ExportFormat::Xlsx => "xlsx",
+        }
+    }
+
+    /// Build the file from the rows
+    ///
+    /// # Arguments
+    ///
+    /// * `headers` - The columns of the file, `display_name` is the header row (or the keys of the objects)
+    /// * `rows` - The rows of the file
+    /// * `options` - The values exported by every format, the byte order mark of CSV and TSV and the delimiter of CSV
+    pub fn render(&self, headers: &[TableHeader], rows: &[Value], options: &CsvOptions) -> Vec<u8> {
+        match self {
+            ExportFormat::Csv => to_csv(headers, rows, options).into_bytes(),
+            ExportFormat::Tsv => to_tsv(headers, rows, options).into_bytes(),
+            ExportFormat::Json => to_json(headers, rows, options.values).into_bytes(),
+            ExportFormat::Ndjson => to_ndjson(headers, rows, options.values).into_bytes(),
+            ExportFormat::Xlsx => to_xlsx(headers, rows, options.values),
+        }
+    }
+}
+
+/// The rows of an export
+///
+/// The current page and the selected rows are built from the rows displayed by the table,
+/// the filtered rows and every row are exported by the back end in server mode (see `DownloadDataRequest::scope`).
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportScope {
+    /// The rows of the displayed page
+    CurrentPage,
+    /// The selected rows
+    Selected,
+    /// Every row matching the search and the filters, in the sort order of the table
+    #[default]
+    Filtered,
+    /// Every row, ignoring the search and the filters
+    All,
+}
+
+impl ExportScope {
+    /// Every scope, in the order of the scope menu
+    pub fn all() -> Vec<ExportScope> {
+        vec![ExportScope::CurrentPage, ExportScope::Selected, ExportScope::Filtered, ExportScope::All]
+    }
+
+    /// The label of the scope menu
+    pub fn label(&self) -> &'static str {
+        match self {
+            ExportScope::CurrentPage => "Current Page",
+            ExportScope::Selected => "Selected Rows",
+            ExportScope::Filtered => "All Filtered Rows",
+            ExportScope::All => "Everything",
+        }
+    }
+
+    /// Whether the rows are built from the rows displayed by the table, even in server mode
+    pub fn is_displayed_rows(&self) -> bool {
+        matches!(self, ExportScope::CurrentPage | ExportScope::Selected)
+    }
+}
+
+/// A typed cell of a JSON or XLSX file
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExportCell {
+    Empty,
+    Text(String),
+    Number(f64),
+    Bool(bool),
+    /// The local date of a `Date` column
+    Date(chrono::NaiveDateTime),
+    /// The local date and time of a `DateTime` column
+    DateTime(chrono::NaiveDateTime),
+}
+
+impl ExportCell {
+    /// The typed cell of a value, every formatted value is text and `null` is empty
+    pub fn of(header: &TableHeader, row: &Value, values: ExportValues) -> Self {
+        if values == ExportValues::Raw && header.is_null(row) {
+            return ExportCell::Empty;
+        }
+        let text = values.value(header, row);
+        if text.is_empty() {
+            return ExportCell::Empty;
+        }
+        if values == ExportValues::Formatted {
+            return ExportCell::Text(text);
+        }
+        if let Some(date) = header.find_datetime(row) {
+            return match header.kind {
+                ColumnKind::Date => ExportCell::Date(date),
+                _ => ExportCell::DateTime(date),
+            };
+        }
+        match (&header.kind, text.as_str()) {
+            (ColumnKind::Boolean, "true") => ExportCell::Bool(true),
+            (ColumnKind::Boolean, "false") => ExportCell::Bool(false),
+            (kind, _) if kind.is_numeric() || header.currency_format.is_some() => match text.trim().parse::<f64>() {
+                Ok(number) if number.is_finite() => ExportCell::Number(number),
+                _ => ExportCell::Text(text),
+            },
+            _ => ExportCell::Text(text),
+        }
+    }
+
+    fn to_json(&self, text: impl FnOnce() -> String) -> Value {
+        match self {
+            ExportCell::Empty => Value::Null,
+            ExportCell::Text(text) => Value::String(text.clone()),
+            ExportCell::Number(number) if number.fract() == 0.0 && number.abs() < 9_007_199_254_740_992.0 => Value::from(*number as i64),
+            ExportCell::Number(number) => Value::from(*number),
+            ExportCell::Bool(value) => Value::Bool(*value),
+            // Dates stay ISO 8601 text, as exported to the other formats
+            ExportCell::Date(_) | ExportCell::DateTime(_) => Value::String(text()),
+        }
+    }
+}
+
+/// Which values of the cells are exported
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportValues {
+    /// The values for a file, numbers without formatting and dates as ISO 8601 (see `TableHeader::export_value`)
+    #[default]
+    Raw,
+    /// The values as displayed in the table (see `TableHeader::display_value`)
+    Formatted,
+}
+
+impl ExportValues {
+    /// The value of a cell
+    pub fn value(&self, header: &TableHeader, row: &Value) -> String {
+        match self {
+            ExportValues::Raw => header.export_value(row),
+            ExportValues::Formatted => header.display_value(row),
+        }
+    }
+}
+
+/// Options of a CSV file, `values` also applies to the other formats of `ExportFormat`
+///
+/// # Arguments
+///
+/// * `values` - Export the raw (default) or the formatted values
+/// * `bom` - A boolean to start the file with a UTF-8 byte order mark, so Excel reads non-ASCII text correctly
+/// * `delimiter` - The delimiter of the fields (default `,`)
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct CsvOptions {
+    #[serde(default)]
+    pub values: ExportValues,
+    #[serde(default)]
+    pub bom: bool,
+    pub delimiter: char,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            values: ExportValues::default(),
+            bom: false,
+            delimiter: ',',
+        }
+    }
+}
+
+impl CsvOptions {
+    /// Export the values as displayed in the table
+    pub fn formatted(mut self) -> Self {
+        self.values = ExportValues::Formatted;
+        self
+    }
+
+    /// Start the file with a UTF-8 byte order mark
+    pub fn bom(mut self) -> Self {
+        self.bom = true;
+        self
+    }
+
+    pub fn delimiter(mut self, delimiter: char) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+}
+
+/// Build a RFC 4180 CSV file from the rows, with the `display_name` of the headers as the header row
+///
+/// The records end with CRLF, and the fields containing the delimiter, a quote or a line break are quoted with their quotes doubled.
+pub fn to_csv(headers: &[TableHeader], rows: &[Value], options: &CsvOptions) -> String {
+    let record = |fields: Vec<String>| {
+        fields
+            .iter()
+            .map(|field| csv_field(field, options.delimiter))
+            .collect::<Vec<_>>()
+            .join(&options.delimiter.to_string())
+    };
+    let mut csv = String::new();
+    if options.bom {
+        csv.push('\u{feff}');
+    }
+    csv.push_str(&record(headers.iter().map(|header| header.display_name.clone()).collect()));
+    csv.push_str("\r\n");
+    for row in rows {
+        csv.push_str(&record(headers.iter().map(|header| options.values.value(header, row)).collect()));
+        csv.push_str("\r\n");
+    }
+    csv
+}
+
+fn csv_field(field: &str, delimiter: char) -> String {
+    match field.contains([delimiter, '"', '\n', '\r']) {
+        true => format!("\"{}\"", field.replace('"', "\"\"")),
+        false => field.to_string(),
+    }
+}
+
+/// Build a TSV file from the rows, with the `display_name` of the headers as the header row
+///
+/// TSV has no quoting, so the tabs and line breaks of the values are replaced by spaces.
+pub fn to_tsv(headers: &[TableHeader], rows: &[Value], options: &CsvOptions) -> String {
+    let record = |fields: Vec<String>| {
+        fields
+            .iter()
+            .map(|field| field.replace(['\t', '\n', '\r'], " "))
+            .collect::<Vec<_>>()
+            .join("\t")
+    };
+    let mut tsv = String::new();
+    if options.bom {
+        tsv.push('\u{feff}');
+    }
+    tsv.push_str(&record(headers.iter().map(|header| header.display_name.clone()).collect()));
+    tsv.push('\n');
+    for row in rows {
+        tsv.push_str(&record(headers.iter().map(|header| options.values.value(header, row)).collect()));
+        tsv.push('\n');
+    }
+    tsv
+}
+
+/// Build a JSON array of an object per row, keyed by the `display_name` of the headers in their order
+///
+/// Raw numbers and booleans are typed, dates are ISO 8601 text and `null` stays `null`.
+pub fn to_json(headers: &[TableHeader], rows: &[Value], values: ExportValues) -> String {
+    let objects: Vec<String> = rows.iter().map(|row| json_object(headers, row, values)).collect();
+    format!("[{}]", objects.join(","))
+}
+
+/// Build a newline delimited JSON file, an object per row keyed by the `display_name` of the headers
+pub fn to_ndjson(headers: &[TableHeader], rows: &[Value], values: ExportValues) -> String {
+    rows.iter().map(|row| json_object(headers, row, values) + "\n").collect()
+}
+
+/// Build an XLSX workbook from the rows, with the `display_name` of the headers as a bold header row
+pub fn to_xlsx(headers: &[TableHeader], rows: &[Value], values: ExportValues) -> Vec<u8> {
+    let header: Vec<String> = headers.iter().map(|header| header.display_name.clone()).collect();
+    let cells: Vec<Vec<ExportCell>> = rows
+        .iter()
+        .map(|row| headers.iter().map(|header| ExportCell::of(header, row, values)).collect())
+        .collect();
+    xlsx::workbook(&header, &cells)
+}
+
+// The object is written by hand to keep the keys in the order of the columns
+fn json_object(headers: &[TableHeader], row: &Value, values: ExportValues) -> String {
+    let entries: Vec<String> = headers
+        .iter()
+        .map(|header| {
+            let value = ExportCell::of(header, row, values).to_json(|| values.value(header, row));
+            format!("{}:{}", Value::String(header.display_name.clone()), value)
+        })
+        .collect();
+    format!("{{{}}}", entries.join(","))
+}
+
+const BASE64_ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Encode a file with the standard base64 alphabet and padding, for a binary file returned as text by a back end
+pub fn encode_base64(content: &[u8]) -> String {
+    let mut text = String::with_capacity(content.len().div_ceil(3) * 4);
+    for chunk in content.chunks(3) {
+        let bits = chunk.iter().enumerate().fold(0u32, |bits, (i, byte)| bits | (u32::from(*byte) << (16 - 8 * i)));
+        for i in 0..4 {
+            match i <= chunk.len() {
+                true => text.push(BASE64_ALPHABET[(bits >> (18 - 6 * i) & 0x3f) as usize] as char),
+                false => text.push('='),
+            }
+        }
+    }
+    text
+}
+
+/// Decode a standard base64 text (whitespace is ignored), `None` when the text is not base64
+pub fn decode_base64(text: &str) -> Option<Vec<u8>> {
+    let digits: Vec<u8> = text.bytes().filter(|byte| !byte.is_ascii_whitespace()).collect();
+    let digits = digits.strip_suffix(b"==").or_else(|| digits.strip_suffix(b"=")).unwrap_or(&digits);
+    let mut content = Vec::with_capacity(digits.len() * 3 / 4);
+    for chunk in digits.chunks(4) {
+        if chunk.len() == 1 {
+            return None;
+        }
+        let mut bits = 0u32;
+        for (i, digit) in chunk.iter().enumerate() {
+            let value = BASE64_ALPHABET.iter().position(|d| d == digit)? as u32;
+            bits |= value << (18 - 6 * i);
+        }
+        content.extend_from_slice(&bits.to_be_bytes()[1..chunk.len()]);
+    }
+    Some(content)
+}
+
+/// The file name prefixed with the current local time (e.g. `20240131_235959_trades.csv`)
+pub fn timestamped_file_name(file_name: &str, extension: &str) -> String {
+    let formatted_local = chrono::Utc::now().with_timezone(&chrono::Local).format("%Y%m%d_%H%M%S").to_string();
+    format!("{}_{}.{}", formatted_local, file_name, extension)
+}
+
+/// Download a content as a file through a temporary link to a blob
+///
+/// # Arguments
+///
+/// * `content` - The bytes of the file
+/// * `mime` - The MIME type of the file (see `ExportFormat::mime`)
+/// * `file_name` - The name of the file with its extension
+pub fn download_blob(content: &[u8], mime: &str, file_name: &str) {
+    use wasm_bindgen::JsValue;
+    use web_sys::{
+        js_sys::{Array, Uint8Array},
+        Blob, BlobPropertyBag,
+    };
+    let uint8arr = Uint8Array::new(&unsafe { Uint8Array::view(content) }.into());
+    let array = Array::new();
+    array.push(&uint8arr.buffer());
+    let options = BlobPropertyBag::new();
+    options.set_type(mime);
+    let file = Blob::new_with_u8_array_sequence_and_options(
+        &JsValue::from(array),
+        &options,
+    )
+    .unwrap();
+    let doc = leptos::document();
+    let hyperlink = wasm_bindgen::JsCast::dyn_into::<web_sys::HtmlAnchorElement>(
+        doc.create_element("a").unwrap(),
+    )
+    .unwrap();
+    hyperlink.set_download(file_name);
+    let url = web_sys::Url::create_object_url_with_blob(&file).unwrap();
+    hyperlink.set_href(&url);
+    hyperlink.click();
+    hyperlink.remove();
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn csv_quotes_fields() {
+        let headers = vec![TableHeader::text("name").label("Name, full"), TableHeader::text("note").label("Note")];
+        let rows = vec![
+            json!({"name": "Smith, John", "note": "said \"hi\""}),
+            json!({"name": "plain", "note": "two\r\nlines"}),
+        ];
+        assert_eq!(
+            to_csv(&headers, &rows, &CsvOptions::default()),
+            "\"Name, full\",Note\r\n\"Smith, John\",\"said \"\"hi\"\"\"\r\nplain,\"two\r\nlines\"\r\n"
+        );
+        assert_eq!(
+            to_csv(&headers, &rows[..1], &CsvOptions::default().delimiter(';').bom()),
+            "\u{feff}Name, full;Note\r\nSmith, John;\"said \"\"hi\"\"\"\r\n"
+        );
+    }
+
+    #[test]
+    fn base64_round_trip() {
+        assert_eq!(encode_base64(b""), "");
+        assert_eq!(encode_base64(b"M"), "TQ==");
+        assert_eq!(encode_base64(b"Ma"), "TWE=");
+        assert_eq!(encode_base64(b"Man"), "TWFu");
+        let content: Vec<u8> = (0..=255).collect();
+        for length in 0..content.len() {
+            let encoded = encode_base64(&content[..length]);
+            assert_eq!(decode_base64(&encoded), Some(content[..length].to_vec()), "{}", length);
+        }
+        assert_eq!(decode_base64("TW\nFu"), Some(b"Man".to_vec()));
+        assert_eq!(decode_base64("TWF"), Some(b"Ma".to_vec()));
+        assert_eq!(decode_base64("T"), None);
+        assert_eq!(decode_base64("TW=u"), None);
+        assert_eq!(decode_base64("TWF*"), None);
+    }
+}
@@ -0,0 +1,752 @@
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// A locale preset for the separators of numbers
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Locale {
+    /// `1,234,567.89`
+    #[default]
+    #[serde(rename = "en-US")]
+    EnUs,
+    /// `1.234.567,89`
+    #[serde(rename = "de-DE")]
+    DeDe,
+    /// `1 234 567,89` (narrow no-break space)
+    #[serde(rename = "fr-FR")]
+    FrFr,
+}
+
+impl Locale {
+    /// The BCP 47 tag of the locale (e.g. `en-US`)
+    pub fn tag(&self) -> &'static str {
+        match self {
+            Locale::EnUs => "en-US",
+            Locale::DeDe => "de-DE",
+            Locale::FrFr => "fr-FR",
+        }
+    }
+
+    /// The locale of a BCP 47 tag, only the language is compared when the region is unknown
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let tag = tag.trim().replace('_', "-").to_lowercase();
+        match tag.as_str() {
+            "en-us" => Some(Locale::EnUs),
+            "de-de" => Some(Locale::DeDe),
+            "fr-fr" => Some(Locale::FrFr),
+            _ => match tag.split('-').next() {
+                Some("en") => Some(Locale::EnUs),
+                Some("de") => Some(Locale::DeDe),
+                Some("fr") => Some(Locale::FrFr),
+                _ => None,
+            },
+        }
+    }
+
+    pub fn thousands_separator(&self) -> &'static str {
+        match self {
+            Locale::EnUs => ",",
+            Locale::DeDe => ".",
+            Locale::FrFr => "\u{202f}",
+        }
+    }
+
+    pub fn decimal_separator(&self) -> &'static str {
+        match self {
+            Locale::EnUs => ".",
+            Locale::DeDe | Locale::FrFr => ",",
+        }
+    }
+}
+
+/// How a number is rounded to the decimal places
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RoundingMode {
+    /// Half away from zero (`2.5` is `3`, `-2.5` is `-3`)
+    #[default]
+    HalfUp,
+    /// Half towards zero (`2.5` is `2`)
+    HalfDown,
+    /// Half to the even digit, the banker's rounding (`2.5` is `2`, `3.5` is `4`)
+    HalfEven,
+    /// Towards zero, truncate
+    Down,
+    /// Away from zero
+    Up,
+    /// Towards negative infinity
+    Floor,
+    /// Towards positive infinity
+    Ceiling,
+}
+
+/// How a negative number is displayed
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NegativeStyle {
+    /// `-1,234.50`
+    #[default]
+    Minus,
+    /// `(1,234.50)`
+    Parentheses,
+}
+
+/// The formatting of the numbers of a column
+///
+/// # Arguments
+///
+/// * `decimals` - The number of decimal places, the precision of the column kind is used when none is given
+/// * `rounding` - How the number is rounded to the decimal places
+/// * `thousands_separator` - The separator of the groups of three digits, empty for none
+/// * `decimal_separator` - The separator of the decimal places
+/// * `negative` - How a negative number is displayed
+/// * `in_exports` - A boolean to export the formatted value instead of the raw value
+///
+/// # Example
+///
+/// ```ignore
+/// let format = NumberFormat::locale(Locale::DeDe).decimals(2).parentheses();
+/// assert_eq!(format.format(-1234567.891, None), "(1.234.567,89)");
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct NumberFormat {
+    pub decimals: Option<usize>,
+    #[serde(default)]
+    pub rounding: RoundingMode,
+    pub thousands_separator: String,
+    pub decimal_separator: String,
+    #[serde(default)]
+    pub negative: NegativeStyle,
+    #[serde(default)]
+    pub in_exports: bool,
+}
+
+impl Default for NumberFormat {
+    fn default() -> Self {
+        Self::locale(Locale::default())
+    }
+}
+
+impl NumberFormat {
+    /// The separators of a locale, with the precision of the column kind
+    pub fn locale(locale: Locale) -> Self {
+        Self {
+            decimals: None,
+            rounding: RoundingMode::default(),
+            thousands_separator: locale.thousands_separator().to_string(),
+            decimal_separator: locale.decimal_separator().to_string(),
+            negative: NegativeStyle::default(),
+            in_exports: false,
+        }
+    }
+
+    pub fn decimals(mut self, decimals: usize) -> Self {
+        self.decimals = Some(decimals);
+        self
+    }
+
+    pub fn rounding(mut self, rounding: RoundingMode) -> Self {
+        self.rounding = rounding;
+        self
+    }
+
+    pub fn thousands_separator(mut self, separator: &str) -> Self {
+        self.thousands_separator = separator.to_string();
+        self
+    }
+
+    pub fn decimal_separator(mut self, separator: &str) -> Self {
+        self.decimal_separator = separator.to_string();
+        self
+    }
+
+    /// Display negative numbers in parentheses
+    pub fn parentheses(mut self) -> Self {
+        self.negative = NegativeStyle::Parentheses;
+        self
+    }
+
+    /// Export the formatted value instead of the raw value
+    pub fn in_exports(mut self) -> Self {
+        self.in_exports = true;
+        self
+    }
+
+    /// Format a number
+    ///
+    /// # Arguments
+    ///
+    /// * `number` - The number to format
+    /// * `precision` - The decimal places used when `decimals` is none, the number is not rounded when both are none
+    pub fn format(&self, number: f64, precision: Option<usize>) -> String {
+        if !number.is_finite() {
+            return number.to_string();
+        }
+        let negative = number.is_sign_negative();
+        let (integer, fraction) = self.round(number, precision);
+        let mut formatted = group_digits(&integer, &self.thousands_separator);
+        if !fraction.is_empty() {
+            formatted.push_str(&self.decimal_separator);
+            formatted.push_str(&fraction);
+        }
+        match (negative && !self.rounds_to_zero(number, precision), self.negative) {
+            (false, _) => formatted,
+            (true, NegativeStyle::Minus) => format!("-{}", formatted),
+            (true, NegativeStyle::Parentheses) => format!("({})", formatted),
+        }
+    }
+
+    /// The number is zero once rounded, so it is displayed without a sign
+    pub(crate) fn rounds_to_zero(&self, number: f64, precision: Option<usize>) -> bool {
+        let (integer, fraction) = self.round(number, precision);
+        integer.chars().chain(fraction.chars()).all(|c| c == '0')
+    }
+
+    /// The integer and fraction digits of the absolute value, rounded to the decimal places
+    fn round(&self, number: f64, precision: Option<usize>) -> (String, String) {
+        // The shortest text that reads back as the number, so `1.005` rounds as written
+        let text = number.abs().to_string();
+        let (integer, fraction) = text.split_once('.').unwrap_or((text.as_str(), ""));
+        match self.decimals.or(precision) {
+            Some(decimals) => round_digits(integer, fraction, decimals, self.rounding, number.is_sign_negative()),
+            None => (integer.to_string(), fraction.to_string()),
+        }
+    }
+}
+
+/// Round the digits of a positive number to the decimal places, `negative` is the sign of the number
+fn round_digits(integer: &str, fraction: &str, decimals: usize, rounding: RoundingMode, negative: bool) -> (String, String) {
+    let mut kept = format!("{}{:0<width$}", integer, fraction.get(..decimals).unwrap_or(fraction), width = decimals);
+    let rest = fraction.get(decimals..).unwrap_or("");
+    let has_rest = rest.chars().any(|c| c != '0');
+    let first = rest.chars().next().unwrap_or('0');
+    let beyond_half = first > '5' || (first == '5' && rest.chars().skip(1).any(|c| c != '0'));
+    let is_odd = kept.chars().last().and_then(|c| c.to_digit(10)).is_some_and(|d| d % 2 == 1);
+    let increment = match rounding {
+        RoundingMode::HalfUp => first >= '5',
+        RoundingMode::HalfDown => beyond_half,
+        RoundingMode::HalfEven => beyond_half || (first == '5' && is_odd),
+        RoundingMode::Down => false,
+        RoundingMode::Up => has_rest,
+        RoundingMode::Floor => has_rest && negative,
+        RoundingMode::Ceiling => has_rest && !negative,
+    };
+    if increment {
+        kept = increment_digits(&kept);
+    }
+    let split = kept.len() - decimals;
+    (kept[..split].to_string(), kept[split..].to_string())
+}
+
+/// Add one to the last digit, carrying over to the left
+fn increment_digits(digits: &str) -> String {
+    let mut digits: Vec<u8> = digits.bytes().collect();
+    for digit in digits.iter_mut().rev() {
+        if *digit == b'9' {
+            *digit = b'0';
+        } else {
+            *digit += 1;
+            return String::from_utf8(digits).unwrap_or_default();
+        }
+    }
+    format!("1{}", String::from_utf8(digits).unwrap_or_default())
+}
+
+/// Insert the separator between the groups of three digits
+fn group_digits(integer: &str, separator: &str) -> String {
+    let mut grouped = String::new();
+    for (i, c) in integer.chars().enumerate() {
+        if i > 0 && (integer.len() - i) % 3 == 0 {
+            grouped.push_str(separator);
+        }
+        grouped.push(c);
+    }
+    grouped
+}
+
+/// How the currency is displayed next to the amount
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CurrencyDisplay {
+    /// The symbol of the currency (e.g. `$`), the code when the symbol is unknown
+    #[default]
+    Symbol,
+    /// The code of the currency (e.g. `USD`)
+    Code,
+}
+
+/// The formatting of the amounts of a currency column, the currency code is read from the `currency` column of the header
+///
+/// The decimal places are the minor units of the ISO 4217 code (JPY 0, USD 2, BHD 3) or the precision of a crypto ticker,
+/// unless the `NumberFormat` of the header sets `decimals`. Unknown codes use 2 decimal places.
+///
+/// # Arguments
+///
+/// * `locale` - The placement of the symbol (before in `en-US`, after in `de-DE` and `fr-FR`) and the separators when the header has no `NumberFormat`
+/// * `display` - Display the symbol or the code of the currency
+/// * `crypto` - The precision of the crypto tickers, displayed as a code after the amount (default BTC 8, ETH 6, USDT 2, USDC 2)
+///
+/// # Example
+///
+/// ```ignore
+/// let format = CurrencyFormat::locale(Locale::DeDe).crypto("SOL", 4);
+/// assert_eq!(format.format(1234.5, "EUR", None), "1.234,50\u{a0}€");
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct CurrencyFormat {
+    #[serde(default)]
+    pub locale: Locale,
+    #[serde(default)]
+    pub display: CurrencyDisplay,
+    #[serde(default = "default_crypto")]
+    pub crypto: Vec<(String, usize)>,
+}
+
+fn default_crypto() -> Vec<(String, usize)> {
+    vec![("BTC".to_string(), 8), ("ETH".to_string(), 6), ("USDT".to_string(), 2), ("USDC".to_string(), 2)]
+}
+
+impl Default for CurrencyFormat {
+    fn default() -> Self {
+        Self::locale(Locale::default())
+    }
+}
+
+impl CurrencyFormat {
+    pub fn locale(locale: Locale) -> Self {
+        Self {
+            locale,
+            display: CurrencyDisplay::default(),
+            crypto: default_crypto(),
+        }
+    }
+
+    /// Display the code of the currency instead of the symbol
+    pub fn code(mut self) -> Self {
+        self.display = CurrencyDisplay::Code;
+        self
+    }
+
+    /// Set the precision of a crypto ticker
+    pub fn crypto(mut self, ticker: &str, precision: usize) -> Self {
+        let ticker = ticker.trim().to_uppercase();
+        self.crypto.retain(|(t, _)| *t != ticker);
+        self.crypto.push((ticker, precision));
+        self
+    }
+
+    /// The precision of a crypto ticker, `None` when the code is not a configured ticker
+    pub fn crypto_precision(&self, code: &str) -> Option<usize> {
+        self.crypto
+            .iter()
+            .find(|(ticker, _)| ticker.eq_ignore_ascii_case(code.trim()))
+            .map(|(_, precision)| *precision)
+    }
+
+    /// The decimal places of a currency code
+    pub fn decimals_of(&self, code: &str) -> usize {
+        self.crypto_precision(code).or_else(|| iso_minor_units(code)).unwrap_or(2)
+    }
+
+    /// Format an amount
+    ///
+    /// # Arguments
+    ///
+    /// * `amount` - The amount to format
+    /// * `code` - The currency code (e.g. `USD`) or crypto ticker (e.g. `BTC`)
+    /// * `number_format` - Optional number format of the header, the separators of the locale are used when none is given
+    pub fn format(&self, amount: f64, code: &str, number_format: Option<&NumberFormat>) -> String {
+        let code = code.trim().to_uppercase();
+        let number_format = number_format.cloned().unwrap_or_else(|| NumberFormat::locale(self.locale));
+        let decimals = self.decimals_of(&code);
+        let number = number_format.format(amount.abs(), Some(decimals));
+        let symbol = match (self.display, currency_symbol(&code)) {
+            (CurrencyDisplay::Symbol, Some(symbol)) if self.crypto_precision(&code).is_none() => Some(symbol),
+            _ => None,
+        };
+        let amount_text = match (symbol, self.locale) {
+            (_, _) if code.is_empty() => number,
+            (Some(symbol), Locale::EnUs) => format!("{}{}", symbol, number),
+            (Some(symbol), _) => format!("{}\u{a0}{}", number, symbol),
+            (None, Locale::EnUs) if self.crypto_precision(&code).is_none() => format!("{}\u{a0}{}", code, number),
+            (None, _) => format!("{}\u{a0}{}", number, code),
+        };
+        let is_zero = number_format.rounds_to_zero(amount, Some(decimals));
+        match (amount.is_sign_negative() && !is_zero, number_format.negative) {
+            (false, _) => amount_text,
+            (true, NegativeStyle::Minus) => format!("-{}", amount_text),
+            (true, NegativeStyle::Parentheses) => format!("({})", amount_text),
+        }
+    }
+}
+
+/// The minor units (decimal places) of an ISO 4217 currency code, `None` when the code is unknown
+pub fn iso_minor_units(code: &str) -> Option<usize> {
+    match code.trim().to_uppercase().as_str() {
+        "BIF" | "CLP" | "DJF" | "GNF" | "ISK" | "JPY" | "KMF" | "KRW" | "PYG" | "RWF" | "UGX" | "UYI" | "VND" | "VUV" | "XAF" | "XOF" | "XPF" => Some(0),
+        "BHD" | "IQD" | "JOD" | "KWD" | "LYD" | "OMR" | "TND" => Some(3),
+        "CLF" | "UYW" => Some(4),
+        "AED" | "ARS" | "AUD" | "BDT" | "BRL" | "CAD" | "CHF" | "CNY" | "COP" | "CZK" | "DKK" | "EGP" | "EUR" | "GBP" | "HKD" | "HUF"
+        | "IDR" | "ILS" | "INR" | "MXN" | "MYR" | "NGN" | "NOK" | "NZD" | "PEN" | "PHP" | "PKR" | "PLN" | "QAR" | "RON" | "RUB" | "SAR"
+        | "SEK" | "SGD" | "THB" | "TRY" | "TWD" | "UAH" | "USD" | "ZAR" => Some(2),
+        _ => None,
+    }
+}
+
+/// The symbol of a currency code, `None` when the code has no known symbol
+pub fn currency_symbol(code: &str) -> Option<&'static str> {
+    match code.trim().to_uppercase().as_str() {
+        "USD" => Some("$"),
+        "EUR" => Some("€"),
+        "GBP" => Some("£"),
+        "JPY" | "CNY" => Some("¥"),
+        "KRW" => Some("₩"),
+        "INR" => Some("₹"),
+        "PHP" => Some("₱"),
+        "RUB" => Some("₽"),
+        "TRY" => Some("₺"),
+        "ILS" => Some("₪"),
+        "NGN" => Some("₦"),
+        "UAH" => Some("₴"),
+        "VND" => Some("₫"),
+        "THB" => Some("฿"),
+        "PLN" => Some("zł"),
+        "BRL" => Some("R$"),
+        "CAD" => Some("CA$"),
+        "AUD" => Some("A$"),
+        "HKD" => Some("HK$"),
+        "NZD" => Some("NZ$"),
+        "SGD" => Some("S$"),
+        "MXN" => Some("MX$"),
+        _ => None,
+    }
+}
+
+/// How a number is read as a Unix timestamp
+///
+/// JSON numbers are always read as Unix timestamps, numeric strings only with an explicit `Seconds` or `Millis` (so `"2024"` is not a timestamp).
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum UnixUnit {
+    /// Milliseconds when the number is above `100_000_000_000` (March 1973 in milliseconds), seconds otherwise
+    #[default]
+    Auto,
+    Seconds,
+    Millis,
+}
+
+/// The zone the timestamps are displayed in
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DateZone {
+    /// The local zone of the browser
+    #[default]
+    Local,
+    Utc,
+    /// A fixed offset from UTC in seconds (e.g. `8 * 3600` for UTC+08:00)
+    Offset(i32),
+    /// An IANA time zone with its daylight saving time (e.g. `Europe/Paris`), needs the `tz` feature,
+    /// the local zone is used without it or when the name is unknown
+    Named(String),
+}
+
+impl DateZone {
+    /// An IANA time zone (e.g. `Europe/Paris`), see `DateZone::Named`
+    pub fn named(name: &str) -> Self {
+        DateZone::Named(name.to_string())
+    }
+}
+
+/// How a timestamp is displayed
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DateDisplay {
+    /// `%Y-%m-%d` for a `Date` column and `%Y-%m-%d %H:%M:%S` for a `DateTime` column
+    #[default]
+    Default,
+    /// A strftime pattern (e.g. `%d %b %Y %H:%M`)
+    Pattern(String),
+    /// Relative to now (e.g. `3 hours ago`, `in 2 days`)
+    Relative,
+}
+
+/// The parsing, zone and display of the timestamps of a `Date` or `DateTime` column
+///
+/// RFC 3339, `YYYY-MM-DD HH:MM:SS` (UTC), `YYYY-MM-DD` (UTC midnight) and JSON numbers as Unix timestamps are always parsed,
+/// the custom `formats` are tried after them (with `%z` for an offset, UTC otherwise). Numeric strings are read as Unix timestamps
+/// only with an explicit `unix` unit.
+///
+/// # Arguments
+///
+/// * `formats` - Custom strftime formats to parse the values with
+/// * `unix` - How numbers are read as Unix timestamps
+/// * `zone` - The zone the timestamps are converted to (default the local zone of the browser), see `DateZone`
+/// * `display` - The strftime pattern or relative display
+///
+/// # Example
+///
+/// ```ignore
+/// let format = DateFormat::default().parse_with("%d/%m/%Y %H:%M").zone(DateZone::Utc).pattern("%d %b %Y");
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+pub struct DateFormat {
+    #[serde(default)]
+    pub formats: Vec<String>,
+    #[serde(default)]
+    pub unix: UnixUnit,
+    #[serde(default)]
+    pub zone: DateZone,
+    #[serde(default)]
+    pub display: DateDisplay,
+}
+
+impl DateFormat {
+    /// Also parse the values with this strftime format
+    pub fn parse_with(mut self, format: &str) -> Self {
+        self.formats.push(format.to_string());
+        self
+    }
+
+    pub fn unix(mut self, unix: UnixUnit) -> Self {
+        self.unix = unix;
+        self
+    }
+
+    pub fn zone(mut self, zone: DateZone) -> Self {
+        self.zone = zone;
+        self
+    }
+
+    /// Display the timestamps with a strftime pattern
+    pub fn pattern(mut self, pattern: &str) -> Self {
+        self.display = DateDisplay::Pattern(pattern.to_string());
+        self
+    }
+
+    /// Display the timestamps relative to now
+    pub fn relative(mut self) -> Self {
+        self.display = DateDisplay::Relative;
+        self
+    }
+
+    /// Parse a JSON value, numbers are Unix timestamps, `None` when no format matches
+    pub fn parse_value(&self, value: &Value) -> Option<DateTime<FixedOffset>> {
+        match value {
+            Value::Number(number) => from_unix(number.as_f64()?, self.unix),
+            Value::String(raw) => self.parse(raw),
+            _ => None,
+        }
+    }
+
+    /// Parse a raw text, numbers are Unix timestamps only with an explicit `unix` unit, `None` when no format matches
+    pub fn parse(&self, raw: &str) -> Option<DateTime<FixedOffset>> {
+        let raw = raw.trim();
+        if let (UnixUnit::Seconds | UnixUnit::Millis, Ok(number)) = (self.unix, raw.parse::<f64>()) {
+            return from_unix(number, self.unix);
+        }
+        parse_datetime(raw).or_else(|| {
+            self.formats.iter().find_map(|format| {
+                DateTime::parse_from_str(raw, format)
+                    .ok()
+                    .or_else(|| NaiveDateTime::parse_from_str(raw, format).ok().map(|d| d.and_utc().fixed_offset()))
+                    .or_else(|| {
+                        NaiveDate::parse_from_str(raw, format)
+                            .ok()
+                            .and_then(|d| d.and_hms_opt(0, 0, 0))
+                            .map(|d| d.and_utc().fixed_offset())
+                    })
+            })
+        })
+    }
+
+    /// Convert a timestamp to the zone
+    pub fn convert(&self, date: DateTime<FixedOffset>) -> DateTime<FixedOffset> {
+        match &self.zone {
+            DateZone::Local => date.with_timezone(&chrono::Local).fixed_offset(),
+            DateZone::Utc => date.with_timezone(&Utc).fixed_offset(),
+            DateZone::Offset(seconds) => match FixedOffset::east_opt(*seconds) {
+                Some(offset) => date.with_timezone(&offset),
+                None => date,
+            },
+            DateZone::Named(name) => to_named_zone(date, name),
+        }
+    }
+
+    /// Format a raw value, `None` when the value is not a timestamp
+    ///
+    /// # Arguments
+    ///
+    /// * `raw` - The raw value
+    /// * `default_pattern` - The pattern of `DateDisplay::Default`
+    pub fn format(&self, raw: &str, default_pattern: &str) -> Option<String> {
+        self.parse(raw).map(|date| self.display(date, default_pattern))
+    }
+
+    /// Display a timestamp converted to the zone
+    ///
+    /// # Arguments
+    ///
+    /// * `date` - The timestamp
+    /// * `default_pattern` - The pattern of `DateDisplay::Default`
+    pub fn display(&self, date: DateTime<FixedOffset>, default_pattern: &str) -> String {
+        let date = self.convert(date);
+        match &self.display {
+            DateDisplay::Default => date.format(default_pattern).to_string(),
+            DateDisplay::Pattern(pattern) => date.format(pattern).to_string(),
+            DateDisplay::Relative => format_relative(date.with_timezone(&Utc), Utc::now()),
+        }
+    }
+}
+
+/// Convert a timestamp to an IANA time zone, to the local zone when the name is unknown
+#[cfg(feature = "tz")]
+fn to_named_zone(date: DateTime<FixedOffset>, name: &str) -> DateTime<FixedOffset> {
+    match name.parse::<chrono_tz::Tz>() {
+        Ok(zone) => date.with_timezone(&zone).fixed_offset(),
+        Err(_) => date.with_timezone(&chrono::Local).fixed_offset(),
+    }
+}
+
+#[cfg(not(feature = "tz"))]
+fn to_named_zone(date: DateTime<FixedOffset>, _name: &str) -> DateTime<FixedOffset> {
+    date.with_timezone(&chrono::Local).fixed_offset()
+}
+
+/// Parse a JSON number as a Unix timestamp (seconds or milliseconds) or a string with `parse_datetime`
+pub(crate) fn parse_datetime_value(value: &Value) -> Option<DateTime<FixedOffset>> {
+    match value {
+        Value::Number(number) => from_unix(number.as_f64()?, UnixUnit::Auto),
+        Value::String(raw) => parse_datetime(raw),
+        _ => None,
+    }
+}
+
+/// Parse an RFC 3339 timestamp, a `YYYY-MM-DD HH:MM:SS` timestamp (UTC) or a `YYYY-MM-DD` date (UTC midnight)
+pub(crate) fn parse_datetime(raw: &str) -> Option<DateTime<FixedOffset>> {
+    let raw = raw.trim();
+    DateTime::parse_from_rfc3339(raw)
+        .ok()
+        .or_else(|| {
+            ["%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M:%S%.f"]
+                .iter()
+                .find_map(|f| NaiveDateTime::parse_from_str(raw, f).ok())
+                .or_else(|| NaiveDate::parse_from_str(raw, "%Y-%m-%d").ok().and_then(|d| d.and_hms_opt(0, 0, 0)))
+                .map(|d| d.and_utc().fixed_offset())
+        })
+}
+
+fn from_unix(number: f64, unix: UnixUnit) -> Option<DateTime<FixedOffset>> {
+    if !number.is_finite() {
+        return None;
+    }
+    let millis = match unix {
+        UnixUnit::Auto if number.abs() >= 100_000_000_000.0 => number,
+        UnixUnit::Auto | UnixUnit::Seconds => number * 1000.0,
+        UnixUnit::Millis => number,
+    };
+    Utc.timestamp_millis_opt(millis.round() as i64).single().map(|d| d.fixed_offset())
+}
+
+/// Format a timestamp relative to now, `just now` under a minute
+fn format_relative(date: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let seconds = (now - date).num_seconds();
+    let elapsed = seconds.unsigned_abs();
+    let (count, unit) = match elapsed {
+        0..=59 => return "just now".to_string(),
+        60..=3_599 => (elapsed / 60, "minute"),
+        3_600..=86_399 => (elapsed / 3_600, "hour"),
+        86_400..=2_591_999 => (elapsed / 86_400, "day"),
+        2_592_000..=31_535_999 => (elapsed / 2_592_000, "month"),
+        _ => (elapsed / 31_536_000, "year"),
+    };
+    let plural = if count == 1 { "" } else { "s" };
+    match seconds >= 0 {
+        true => format!("{} {}{} ago", count, unit, plural),
+        false => format!("in {} {}{}", count, unit, plural),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rounding_modes() {
+        let round = |number: f64, rounding: RoundingMode| NumberFormat::default().rounding(rounding).format(number, Some(0));
+        let cases = [
+            (RoundingMode::HalfUp, ["3", "-3", "2", "4", "-2"]),
+            (RoundingMode::HalfDown, ["2", "-2", "2", "3", "-2"]),
+            (RoundingMode::HalfEven, ["2", "-2", "2", "4", "-2"]),
+            (RoundingMode::Down, ["2", "-2", "2", "3", "-2"]),
+            (RoundingMode::Up, ["3", "-3", "3", "4", "-3"]),
+            (RoundingMode::Floor, ["2", "-3", "2", "3", "-3"]),
+            (RoundingMode::Ceiling, ["3", "-2", "3", "4", "-2"]),
+        ];
+        for (rounding, expected) in cases {
+            let actual = [2.5, -2.5, 2.1, 3.5, -2.1].map(|number| round(number, rounding));
+            assert_eq!(actual, expected, "{:?}", rounding);
+        }
+    }
+
+    #[test]
+    fn rounds_as_written() {
+        let format = NumberFormat::default();
+        assert_eq!(format.format(1.005, Some(2)), "1.01");
+        assert_eq!(format.format(9.995, Some(2)), "10.00");
+        assert_eq!(format.format(999_999.5, Some(0)), "1,000,000");
+        assert_eq!(format.format(-0.004, Some(2)), "0.00");
+        assert_eq!(format.format(1.5, None), "1.5");
+        assert_eq!(format.clone().decimals(3).format(1.5, Some(1)), "1.500");
+    }
+
+    #[test]
+    fn currency_sign_of_rounded_amounts() {
+        let format = CurrencyFormat::default().crypto("1INCH", 2);
+        assert_eq!(format.format(-0.001, "1INCH", None), "0.00\u{a0}1INCH");
+        assert_eq!(format.format(-1.5, "1INCH", None), "-1.50\u{a0}1INCH");
+        assert_eq!(format.format(-0.4, "JPY", None), "¥0");
+        assert_eq!(format.format(-0.6, "JPY", None), "-¥1");
+        assert_eq!(format.format(-1234.5, "EUR", Some(&NumberFormat::locale(Locale::DeDe).parentheses())), "(€1.234,50)");
+    }
+
+    #[test]
+    fn numeric_strings_are_not_unix_timestamps() {
+        use serde_json::json;
+        assert_eq!(parse_datetime("2024"), None);
+        assert_eq!(parse_datetime_value(&json!("2024")), None);
+        assert_eq!(parse_datetime_value(&json!(1_700_000_000)).map(|d| d.timestamp()), Some(1_700_000_000));
+        assert_eq!(parse_datetime_value(&json!(1_700_000_000_000i64)).map(|d| d.timestamp()), Some(1_700_000_000));
+        assert_eq!(DateFormat::default().parse("2024"), None);
+        assert_eq!(DateFormat::default().parse_with("%Y%m%d").parse("20240131").map(|d| d.timestamp()), Some(1_706_659_200));
+        assert_eq!(DateFormat::default().unix(UnixUnit::Seconds).parse("1700000000").map(|d| d.timestamp()), Some(1_700_000_000));
+        assert_eq!(DateFormat::default().unix(UnixUnit::Millis).parse_value(&json!(1_700_000_000_000i64)).map(|d| d.timestamp()), Some(1_700_000_000));
+    }
+
+    #[test]
+    fn separators() {
+        assert_eq!(NumberFormat::default().format(1_234_567.891, Some(2)), "1,234,567.89");
+        assert_eq!(NumberFormat::locale(Locale::DeDe).format(1_234_567.891, Some(2)), "1.234.567,89");
+        assert_eq!(NumberFormat::locale(Locale::FrFr).format(1_234_567.891, Some(2)), "1\u{202f}234\u{202f}567,89");
+        assert_eq!(NumberFormat::default().thousands_separator("").format(1_234_567.0, Some(0)), "1234567");
+        assert_eq!(NumberFormat::default().thousands_separator("'").decimal_separator(".").format(123_456.5, Some(1)), "123'456.5");
+        assert_eq!(NumberFormat::default().format(123.0, Some(0)), "123");
+        assert_eq!(NumberFormat::locale(Locale::DeDe).parentheses().format(-1_234_567.891, Some(2)), "(1.234.567,89)");
+    }
+
+    #[cfg(feature = "tz")]
+    #[test]
+    fn named_zone_follows_daylight_saving_time() {
+        let format = DateFormat::default().zone(DateZone::named("Europe/Paris")).pattern("%Y-%m-%d %H:%M %:z");
+        assert_eq!(format.format("2024-03-31T00:30:00Z", "").as_deref(), Some("2024-03-31 01:30 +01:00"));
+        assert_eq!(format.format("2024-03-31T01:30:00Z", "").as_deref(), Some("2024-03-31 03:30 +02:00"));
+        assert_eq!(format.format("2024-10-27T00:30:00Z", "").as_deref(), Some("2024-10-27 02:30 +02:00"));
+        assert_eq!(format.format("2024-10-27T01:30:00Z", "").as_deref(), Some("2024-10-27 02:30 +01:00"));
+    }
+
+    #[test]
+    fn unknown_named_zone_is_local() {
+        let date = parse_datetime("2024-03-31T01:30:00Z").unwrap();
+        let local = DateFormat::default().convert(date);
+        assert_eq!(DateFormat::default().zone(DateZone::named("Not/AZone")).convert(date), local);
+    }
+}
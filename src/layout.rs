@@ -0,0 +1,119 @@
+use serde::{Deserialize, Serialize};
+
+/// The screen width where the table switches from the mobile layout to the desktop layout
+///
+/// The classes are written out in full so the Tailwind scanner finds them. A `Custom` screen must be in the Tailwind config,
+/// and its classes are given in full, as string literals the scanner can see.
+/// The default is the `px924` screen the table has always used.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Breakpoint {
+    /// 640px
+    Sm,
+    /// 768px
+    Md,
+    /// 1024px
+    Lg,
+    /// 1280px
+    Xl,
+    /// 1536px
+    Xxl,
+    /// A custom screen of the Tailwind config, with the full classes of the mobile markup, the desktop rows and the desktop cells
+    /// (e.g. `px924:hidden`, `hidden px924:table-row` and `hidden px924:table-cell`)
+    Custom {
+        mobile_only: String,
+        desktop_row: String,
+        desktop_cell: String,
+    },
+}
+
+impl Default for Breakpoint {
+    fn default() -> Self {
+        Self::custom("px924:hidden", "hidden px924:table-row", "hidden px924:table-cell")
+    }
+}
+
+impl Breakpoint {
+    /// A custom screen from its full classes, see `Breakpoint::Custom`
+    pub fn custom(mobile_only: &str, desktop_row: &str, desktop_cell: &str) -> Self {
+        Breakpoint::Custom {
+            mobile_only: mobile_only.to_string(),
+            desktop_row: desktop_row.to_string(),
+            desktop_cell: desktop_cell.to_string(),
+        }
+    }
+
+    /// Hidden from the breakpoint, for the mobile markup
+    pub fn mobile_only(&self) -> String {
+        match self {
+            Breakpoint::Sm => "sm:hidden".to_string(),
+            Breakpoint::Md => "md:hidden".to_string(),
+            Breakpoint::Lg => "lg:hidden".to_string(),
+            Breakpoint::Xl => "xl:hidden".to_string(),
+            Breakpoint::Xxl => "2xl:hidden".to_string(),
+            Breakpoint::Custom { mobile_only, .. } => mobile_only.clone(),
+        }
+    }
+
+    /// A row hidden below the breakpoint
+    pub fn desktop_row(&self) -> String {
+        match self {
+            Breakpoint::Sm => "hidden sm:table-row".to_string(),
+            Breakpoint::Md => "hidden md:table-row".to_string(),
+            Breakpoint::Lg => "hidden lg:table-row".to_string(),
+            Breakpoint::Xl => "hidden xl:table-row".to_string(),
+            Breakpoint::Xxl => "hidden 2xl:table-row".to_string(),
+            Breakpoint::Custom { desktop_row, .. } => desktop_row.clone(),
+        }
+    }
+
+    /// A cell hidden below the breakpoint
+    pub fn desktop_cell(&self) -> String {
+        match self {
+            Breakpoint::Sm => "hidden sm:table-cell".to_string(),
+            Breakpoint::Md => "hidden md:table-cell".to_string(),
+            Breakpoint::Lg => "hidden lg:table-cell".to_string(),
+            Breakpoint::Xl => "hidden xl:table-cell".to_string(),
+            Breakpoint::Xxl => "hidden 2xl:table-cell".to_string(),
+            Breakpoint::Custom { desktop_cell, .. } => desktop_cell.clone(),
+        }
+    }
+}
+
+/// How the rows are displayed below the breakpoint
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MobileLayout {
+    /// A row per record showing `key_to_display_in_mobile`, expanding to a row per column
+    #[default]
+    Accordion,
+    /// A card per record with every column stacked
+    Cards,
+    /// The desktop table, scrolled horizontally
+    Scroll,
+    /// The desktop table without the columns whose `priority` is above `visible`
+    Priority { visible: u8 },
+}
+
+impl MobileLayout {
+    /// Whether the desktop table is also the mobile table
+    pub fn uses_table(&self) -> bool {
+        matches!(self, MobileLayout::Scroll | MobileLayout::Priority { .. })
+    }
+
+    /// The classes of a column of this priority, hidden below the breakpoint when its priority is too low
+    pub fn column_class(&self, priority: u8, breakpoint: &Breakpoint) -> String {
+        match self {
+            MobileLayout::Priority { visible } if priority > *visible => breakpoint.desktop_cell(),
+            _ => String::new(),
+        }
+    }
+
+    /// The classes of a desktop row, hidden below the breakpoint unless the table is also the mobile table
+    pub fn row_class(&self, breakpoint: &Breakpoint) -> String {
+        match self.uses_table() {
+            true => String::new(),
+            false => breakpoint.desktop_row(),
+        }
+    }
+}
@@ -1,6 +1,24 @@
 
 pub mod model;
+pub mod format;
+pub mod client;
+pub mod source;
+pub mod state;
+pub mod selection;
+pub mod style;
+pub mod theme;
+pub mod layout;
+pub mod export;
+mod xlsx;
 use model::*;
+use source::DataSource;
+use state::TableState;
+use selection::{BulkAction, BulkSelection, RowSelection, SelectionMode};
+use style::{rule_classes, StyleRule};
+use theme::TableTheme;
+use layout::{Breakpoint, MobileLayout};
+use export::{download_blob, timestamped_file_name, CsvOptions, ExportFormat, ExportScope};
+use std::rc::Rc;
 use leptos::*;
 use serde_json::Value;
 
@@ -11,47 +29,184 @@ use serde_json::Value;
 /// 
 /// * `headers` - headers with extra data for the table
 /// * `data` - data to display in the table
-/// * `offset` - data offset for pagination
-/// * `search` - search string
-/// * `sort` - sort ascending (false), descending (true)
-/// * `sort_by` - sort by column name
-/// * `limit` - number of rows to display per page
-/// * `total` - total number of rows
-/// * `current_page` - current page number
+/// * `state` - pagination, sort, search and filter state of the table, see `TableState`
 /// * `allow_download` - allow download of the table data
 /// * `download_filename` - filename for the downloaded file
-/// * `download_resource` - resource for downloading the data
+/// * `export` - action producing the download file on click in server mode (client mode builds the CSV file from the rows), no download button when none is given
+/// * `table_name` - table name passed to `export`
+/// * `fields` - fields passed to `export`
 /// * `key_to_display_in_mobile` - key to display in mobile view header
+/// * `mode` - `TableMode::Server` (default) displays `data` as is, `TableMode::Client` sorts, searches and paginates `data` in memory and sets `total`
+/// * `selection` - selection of rows with a checkbox column, hidden by default, see `RowSelection`
+/// * `bulk_actions` - actions on the selected rows, shown in the toolbar when rows are selected
+/// * `toolbar` - extra controls shown in the toolbar, next to the download button
+/// * `row_styles` - ordered style rules of the rows, checked against the fields of the row (see `StyleRule::on`), rules without a field never match
+/// * `row_class` - callback returning extra classes of a row, added after the classes of `row_styles`
+/// * `theme` - classes of the parts of the table, the theme of the context (or the daisyUI theme) when none is given, see `TableTheme`
+/// * `breakpoint` - screen width where the table switches to the mobile layout (default the `px924` screen), see `Breakpoint`
+/// * `mobile_layout` - how the rows are displayed below the breakpoint (default `MobileLayout::Accordion`), see `MobileLayout`
+/// * `csv_options` - options of the file built from the rows in client mode, see `CsvOptions`
+/// * `export_formats` - formats of the download picked in the toolbar, requested from `export` in server mode (default CSV only), see `ExportFormat`
+/// * `export_scopes` - rows of the download, picked in the toolbar (default the filtered rows only), the current page and the selected rows are built from the displayed rows even in server mode, see `ExportScope`
 #[allow(non_snake_case)]
 #[component]
 pub fn DataTable(
     headers: RwSignal<Vec<TableHeader>>,
     data: Signal<Vec<Value>>,
-    offset: RwSignal<u32>,
-    search: RwSignal<String>,
-    sort: RwSignal<bool>,
-    sort_by: RwSignal<String>,
-    limit: RwSignal<u32>,
-    total: RwSignal<u32>,
-    current_page: RwSignal<u32>,
+    state: TableState,
     allow_download: RwSignal<bool>,
     download_filename: RwSignal<String>,
-    download_resource: Resource<DownloadDataRequest, Result<String, ServerFnError>>,
-    #[prop(optional)] key_to_display_in_mobile: RwSignal<String>
+    #[prop(optional)] export: Option<Action<DownloadDataRequest, Result<String, ServerFnError>>>,
+    #[prop(optional, into)] table_name: String,
+    #[prop(optional)] fields: RwSignal<String>,
+    #[prop(optional)] key_to_display_in_mobile: RwSignal<String>,
+    #[prop(optional)] mode: TableMode,
+    #[prop(optional)] selection: RowSelection,
+    #[prop(optional)] bulk_actions: Vec<BulkAction>,
+    #[prop(optional, into)] toolbar: ViewFn,
+    #[prop(optional)] row_styles: Vec<StyleRule>,
+    #[prop(optional, into)] row_class: Option<Callback<Value, String>>,
+    #[prop(optional)] theme: Option<TableTheme>,
+    #[prop(optional)] breakpoint: Breakpoint,
+    #[prop(optional)] mobile_layout: MobileLayout,
+    #[prop(optional)] csv_options: CsvOptions,
+    #[prop(optional)] export_formats: Vec<ExportFormat>,
+    #[prop(optional)] export_scopes: Vec<ExportScope>,
 ) -> impl IntoView {
+    let csv_options = store_value(csv_options);
+    let export_formats = match export_formats.is_empty() {
+        true => vec![ExportFormat::Csv],
+        false => export_formats,
+    };
+    let export_format = create_rw_signal(export_formats[0]);
+    let export_formats = store_value(export_formats);
+    let export_scopes = match export_scopes.is_empty() {
+        true => vec![ExportScope::Filtered],
+        false => export_scopes,
+    };
+    // Without an export action, the server mode can only export the displayed rows
+    let export_scopes: Vec<ExportScope> = export_scopes
+        .into_iter()
+        .filter(|scope| mode == TableMode::Client || export.is_some() || scope.is_displayed_rows())
+        .filter(|scope| *scope != ExportScope::Selected || selection.is_enabled())
+        .collect();
+    let export_scope = create_rw_signal(export_scopes.first().copied().unwrap_or_default());
+    let export_scopes = store_value(export_scopes);
+    let table_name = store_value(table_name);
+    let download_request = Callback::new(move |_| {
+        let request = headers.with_untracked(|headers| DownloadDataRequest::from_state(&state, headers))
+            .table_name(&table_name.get_value())
+            .fields(&fields.get_untracked())
+            .format(export_format.get_untracked());
+        // The selected rows of a server table are only unknown when every row matching the query is selected
+        match export_scope.get_untracked() {
+            ExportScope::Selected => request.selected(&selection),
+            scope => request.scope(scope),
+        }
+    });
+    let desktop_row = store_value(mobile_layout.row_class(&breakpoint));
+    let mobile_only = store_value(breakpoint.mobile_only());
+    let column_class = store_value(move |priority: u8| mobile_layout.column_class(priority, &breakpoint));
+    let theme = theme.unwrap_or_else(TableTheme::from_context);
+    provide_context(theme.clone());
+    let theme = store_value(theme);
+    let row_styles = store_value(row_styles);
+    let row_classes = move |row: &Value| {
+        let rules = row_styles.with_value(|rules| rule_classes(rules, None, row));
+        let extra = row_class.map(|row_class| row_class.call(row.clone())).unwrap_or_default();
+        format!("{} {}", rules, extra).trim().to_string()
+    };
     let pages_entries = RwSignal::new(vec![5, 10, 15, 20, 25, 50, 100]);
+    if cfg!(debug_assertions) {
+        create_effect(move |_| {
+            headers.with(|headers| {
+                headers
+                    .iter()
+                    .filter_map(|header| header.validate().err())
+                    .flatten()
+                    .for_each(|error| logging::warn!("leptab: {}", error))
+            })
+        });
+        row_styles.with_value(|rules| {
+            rules
+                .iter()
+                .filter(|rule| rule.field.is_none())
+                .for_each(|rule| logging::warn!("leptab: row style `{}` has no field and never matches, set one with `on`", rule.class))
+        });
+    }
+    let has_filters = move || headers.with(|headers| headers.iter().any(|h| h.filter.is_some()));
+    // In client mode, every row matching the condition, the column filters and the search, in sorted order
+    let matched_rows = create_memo(move |_| match mode {
+        TableMode::Server => Vec::new(),
+        TableMode::Client => {
+            let headers = headers.get();
+            let rows = client::condition_rows(data.get(), &state.condition());
+            let rows = client::filter_rows(&headers, rows, &state.filters());
+            let mut rows = client::search_rows(&headers, rows, &state.search());
+            client::sort_rows(&headers, &mut rows, &state.sort());
+            rows
+        }
+    });
+    create_effect(move |_| {
+        if mode == TableMode::Client {
+            state.set_total(matched_rows.with(|rows| rows.len() as u32));
+        }
+    });
+    let rows = Signal::derive(move || match mode {
+        TableMode::Server => data.get(),
+        TableMode::Client => matched_rows.with(|rows| client::page_rows(rows, state.offset(), state.limit())),
+    });
+    let column_count = move || headers.with(|headers| headers.len()) + usize::from(selection.is_enabled());
+    let page_selected = move || rows.with(|rows| selection.is_all_selected(rows));
+    let show_select_all_pages = move || {
+        selection.mode() == SelectionMode::Multiple && page_selected() && !selection.is_all_pages()
+            && rows.with(|rows| (rows.len() as u32) < state.total())
+    };
+    // Every row of another query is not the selection of every page anymore
+    create_effect(move |previous: Option<()>| {
+        state.search();
+        state.filter();
+        state.filters();
+        state.condition();
+        if previous.is_some() {
+            untrack(|| selection.clear_all_pages());
+        }
+    });
+    let select_all_pages = move || match mode {
+        TableMode::Server => rows.with_untracked(|rows| selection.select_all_pages(rows)),
+        TableMode::Client => matched_rows.with_untracked(|rows| selection.select_rows(rows)),
+    };
+    // Every scope is built from the rows in client mode, the server exports the others with `export`
+    let export_from_rows = move || match export_scope.get() {
+        ExportScope::CurrentPage => true,
+        ExportScope::Selected => mode == TableMode::Client || !selection.is_all_pages(),
+        ExportScope::Filtered | ExportScope::All => mode == TableMode::Client,
+    };
+    let download_rows = move || {
+        let rows = untrack(|| match export_scope.get() {
+            ExportScope::CurrentPage => rows.get(),
+            ExportScope::Selected => selection.selected_rows(),
+            ExportScope::Filtered => matched_rows.get(),
+            ExportScope::All => {
+                let mut rows = data.get();
+                headers.with(|headers| client::sort_rows(headers, &mut rows, &state.sort()));
+                rows
+            }
+        });
+        let format = export_format.get_untracked();
+        let content = headers.with_untracked(|headers| csv_options.with_value(|options| format.render(headers, &rows, options)));
+        download_blob(&content, format.mime(), &timestamped_file_name(&download_filename.get_untracked(), format.extension()));
+    };
     view! {
         <div class="p-1">
             <div class="flex justify-between my-2">
                 <div class="flex flex-auto justify-start gap-2 items-center">
                     <select
-                        class="text-xs border-gray-800 rounded shadow-md select-xs hover:shadow-sm hover:shadow-success bg-base-100"
+                        class=theme.with_value(|theme| theme.select.clone())
                         name="row_slice"
                         on:change=move |e| {
                             let val = event_target_value(&e).parse::<u32>().unwrap();
-                            limit.set(val);
-                            current_page.set(1);
-                            offset.set(0)
+                            state.set_page_size(val);
                         }
                     >
 
@@ -62,7 +217,7 @@ pub fn DataTable(
                                 .map(|page_entry| {
                                     view! {
                                         <option
-                                            prop:selected=limit.get() == page_entry
+                                            prop:selected=state.limit() == page_entry
                                             value=page_entry.to_string()
                                         >
                                             {page_entry}
@@ -73,75 +228,117 @@ pub fn DataTable(
                         }}
 
                     </select>
-                    <Suspense
-                        fallback = move || view! {<div class = "flex justify start gap-2 items-center"><span class = "loading loading-spinner loading-xs"></span><span class = "text-xs opacity-50 font-extralight">"Loading File"</span></div>}
-                    >
-                        <ErrorBoundary
-                            fallback = move |_| view! {<div class = "flex justify start gap-2 items-center"><span class = "text-xs/3 text-error opacity-50 font-extralight">"Error Loading Download File"</span></div>}
-                        >
-                            {
-                                move || {
-                                    download_resource.and_then(|d| {
-                                        match allow_download.get() && total.get().gt(&0u32){
-                                            true => {
-                                                view! {
-                                                    <DownloadCsvAnchor
-                                                        content=d.clone()
-                                                        file_name=download_filename.get()
-                                                    />
-                                                }
-                                            }
-                                            false => view! {}.into_view(),
-                                        }
-                                    
-                                    })
-                                }
-                            }
-                        </ErrorBoundary>
-                    </Suspense>
+                    <Show when=move || allow_download.get() && state.total().gt(&0u32) && export_scopes.with_value(|scopes| !scopes.is_empty())>
+                        <ExportPicker options=export_scopes.get_value() label=ExportScope::label selected=export_scope/>
+                        <ExportPicker options=export_formats.get_value() label=ExportFormat::label selected=export_format/>
+                        {move || match export_from_rows() {
+                            true => view! {
+                                <DownloadButton label=Signal::derive(move || export_format.get().label().to_string()) on_click=download_rows/>
+                            }.into_view(),
+                            false => export.map(|export| view! {
+                                <DownloadExport
+                                    export=export
+                                    request=download_request
+                                    file_name=download_filename
+                                    format=export_format
+                                />
+                            }).into_view(),
+                        }}
+                    </Show>
+                    {toolbar.run()}
+                    <BulkActionBar selection=selection actions=bulk_actions state=state/>
                 </div>
                 <div class="flex flex-auto justify-end gap-1">
                     <div class = "flex gap-1 items-center">
                         <span class="text-xs font-light">"Search : "</span>
                         <input
                             type="text"
-                            class="input input-xs rounded input-info focus:outline-none focus:shadow-outline"
+                            class=theme.with_value(|theme| theme.search_input.clone())
                             placeholder=""
-                            prop:value=search
-                            on:blur=move |event| {
-                                search.set(event_target_value(&event));
-                                current_page.set(1);
-                                offset.set(0);
-                            }
+                            prop:value=move || state.search()
+                            on:blur=move |event| state.set_search(&event_target_value(&event))
                         />
 
                     </div>
                 </div>
             </div>
-            <table class="table table-xs table-zebra-zebra mt-1">
+            <Show when=move || show_select_all_pages() || selection.is_all_pages()>
+                <div class=theme.with_value(|theme| format!("flex justify-center gap-2 items-center {}", theme.banner))>
+                    {move || match selection.is_all_pages() {
+                        true => view! {
+                            <span>{move || format!("{} of {} rows are selected.", selection.count(state.total()), state.total())}</span>
+                            <button class=theme.with_value(|theme| theme.pagination_button.clone()) on:click=move |_| selection.clear()>"Clear selection"</button>
+                        }.into_view(),
+                        false => view! {
+                            <span>{move || format!("All {} rows on this page are selected.", rows.with(|rows| rows.len()))}</span>
+                            <button class=theme.with_value(|theme| theme.pagination_button.clone()) on:click=move |_| select_all_pages()>{format!("Select all {} rows", state.total())}</button>
+                        }.into_view(),
+                    }}
+                </div>
+            </Show>
+            <div class:overflow-x-auto=mobile_layout == MobileLayout::Scroll>
+            <table class=theme.with_value(|theme| theme.table.clone()) class:whitespace-nowrap=mobile_layout == MobileLayout::Scroll>
                 <thead>
-                    <tr class = "hidden px924:contents">
+                    <tr class=desktop_row.get_value()>
+                        <Show when=move || selection.is_enabled()>
+                            <th class=theme.with_value(|theme| format!("w-0 {}", theme.header_cell))>
+                                <Show when=move || selection.mode() == SelectionMode::Multiple>
+                                    <input
+                                        type="checkbox"
+                                        class=theme.with_value(|theme| theme.checkbox.clone())
+                                        prop:checked=page_selected
+                                        on:change=move |_| match untrack(page_selected) {
+                                            true => rows.with_untracked(|rows| selection.deselect_rows(rows)),
+                                            false => rows.with_untracked(|rows| selection.select_rows(rows)),
+                                        }
+                                    />
+                                </Show>
+                            </th>
+                        </Show>
 
                         {move || {
                             headers
                                 .get()
                                 .into_iter()
                                 .map(|i| {
+                                    let class = column_class.with_value(|column_class| column_class(i.priority));
                                     let header = RwSignal::new(i);
-                                    view! { <TableHeader sort_by=sort_by header=header sort=sort/> }
+                                    view! { <TableHeader state=state header=header class=class/> }
                                 })
                                 .collect_view()
                         }}
 
                     </tr>
+                    <Show when=has_filters>
+                        <tr class=desktop_row.get_value()>
+                            <Show when=move || selection.is_enabled()>
+                                <th class=theme.with_value(|theme| theme.filter_cell.clone())></th>
+                            </Show>
+
+                            {move || {
+                                headers
+                                    .get()
+                                    .into_iter()
+                                    .map(|header| {
+                                        let class = column_class.with_value(|column_class| column_class(header.priority));
+                                        view! { <TableFilterCell state=state header=header class=class/> }
+                                    })
+                                    .collect_view()
+                            }}
+
+                        </tr>
+                        <Show when=move || !mobile_layout.uses_table()>
+                            <TableFiltersMobile state=state headers=headers colspan=Signal::derive(column_count) class=mobile_only.get_value()/>
+                        </Show>
+                    </Show>
                 </thead>
                 <tbody>
                     {move || {
-                        match data.get().is_empty() {
+                        match rows.get().is_empty() {
                             true => {
                                 view! {
                                     <tr>
-                                        <td colspan=headers.get().len() class="text-center">
+                                        <td colspan=column_count() class="text-center">
                                             <span class="opacity-50 font-extralight">
                                                 No data available
                                             </span>
@@ -154,143 +351,135 @@ pub fn DataTable(
                                 view! {
                                     {move || {
                                         {
-                                            data.get()
+                                            rows.get()
                                                 .into_iter()
                                                 .enumerate()
                                                 .map(|(index, value)| {
                                                     let hide_per_cell = RwSignal::new(true);
                                                     let key_to_display = key_to_display_in_mobile.get();
-                                                    let header_length = headers.get_untracked().len();
+                                                    let header_length = untrack(column_count);
+                                                    let row = store_value(value.clone());
+                                                    let selection_cell = move || match selection.mode() {
+                                                        SelectionMode::None => view! {}.into_view(),
+                                                        SelectionMode::Single => view! {
+                                                            <input
+                                                                type="radio"
+                                                                class=theme.with_value(|theme| theme.radio.clone())
+                                                                prop:checked=move || row.with_value(|row| selection.is_selected(row))
+                                                                on:click=move |e| e.stop_propagation()
+                                                                on:change=move |_| row.with_value(|row| selection.toggle(row))
+                                                            />
+                                                        }.into_view(),
+                                                        SelectionMode::Multiple => view! {
+                                                            <input
+                                                                type="checkbox"
+                                                                class=theme.with_value(|theme| theme.checkbox.clone())
+                                                                prop:checked=move || row.with_value(|row| selection.is_selected(row))
+                                                                on:click=move |e| e.stop_propagation()
+                                                                on:change=move |_| row.with_value(|row| selection.toggle(row))
+                                                            />
+                                                        }.into_view(),
+                                                    };
 
                                                     let value_clone = value.clone();
+                                                    let row_class = row_classes(&value);
+                                                    let desktop_class = theme.with_value(|theme| format!("{} {} {}", desktop_row.get_value(), theme.row_class(index), row_class));
+                                                    let mobile_class = theme.with_value(|theme| format!("{} {} {}", mobile_only.get_value(), theme.row_class(index), row_class));
+                                                    let mobile_toggle = theme.with_value(|theme| theme.mobile_toggle.clone());
 
-                                                    let header_display = value_clone.get(&key_to_display)
+                                                    let header_display = resolve_path(&value_clone, &key_to_display)
                                                     .map(|value| value.to_string())
                                                     .unwrap_or_else(|| "Click to show more...".to_string()).replace('"', "");
 
                                                     view! {
 
                                                         // =================== DESKTOP VIEW ===================
-                                                        <div class="hidden px924:contents">
-                                                            <tr class = { move || if index % 2 != 0 {"uppercase hover:opacity-50 bg-base-300"} else{"uppercase hover:opacity-50"}}>
-
-                                                                {move || {
-                                                                    headers
-                                                                        .get()
-                                                                        .into_iter()
-                                                                        .map(|header| {
-                                                                            view! {
-                                                                                <td>
-                                                                                    {
-                                                                                        match header.prefix {
-                                                                                            Some(ref p) => view! { <span class="text-xs opacity-50 text-xs/3">{format!("{} ", p)}</span> }.into_view(),
-                                                                                            None => view! {}.into_view()
-                                                                                        }
-                                                                                    }
-                                                                                    {
-                                                                                        let number_style = if header.is_number_styled {
-                                                                                            match header
-                                                                                            .find(&value_clone)
-                                                                                            .parse::<f64>()
-                                                                                            .ok()
-                                                                                            {
-                                                                                                Some(parsed_value) if parsed_value >= 0.0 => "text-success",
-                                                                                                Some(_) => "text-error",
-                                                                                                None => "",
-                                                                                            }
-                                                                                        }else {""};
-                                                                                        let style_when_success = match header
-                                                                                            .find(&value_clone)
-                                                                                            .to_uppercase().contains(&header.style_when_success.to_uppercase()) && !header.style_when_success.is_empty()
-                                                                                        {
-                                                                                            true => "text-success",
-                                                                                            false => "",
-                                                                                        };
-                                                                                        let style_when_error = match header
-                                                                                            .find(&value_clone)
-                                                                                            .to_uppercase().contains(&header.style_when_error.to_uppercase()) && !header.style_when_error.is_empty()
-                                                                                        {
-                                                                                            true => "text-error",
-                                                                                            false => "",
-                                                                                        };
-                                                                                        let case_style = match header.to_uppercase {
-                                                                                            true => "uppercase",
-                                                                                            false => "",
-                                                                                        };
-                                                                                        let style = format!(
-                                                                                            "{} {} {} {}",
-                                                                                            number_style,
-                                                                                            style_when_success,
-                                                                                            style_when_error,
-                                                                                            case_style,
-                                                                                        );
-                                                                                        view! { <span class=style>{header.find(&value_clone)}</span> }
-                                                                                    }
-                                                                                    {match header.is_currency {
-                                                                                        true => {
-                                                                                            let has_value = header.find(&value_clone).parse::<f64>().is_ok();
-                                                                                            if has_value {
-                                                                                                view! {
-                                                                                                    <span class="text-xs opacity-50 text-xs/3">
-                                                                                                        {format!(" {}", header.find_currency(&value_clone))}
-                                                                                                    </span>
-                                                                                                }
-                                                                                            }else{
-                                                                                                view! { <span></span> }
-                                                                                            }
-                                                                                            
-                                                                                        }
-                                                                                        false => view! { <span></span> },
-                                                                                    }}
-
-                                                                                </td>
-                                                                            }
-                                                                        })
-                                                                        .collect_view()
-                                                                }}
-
-                                                            </tr>
-                                                        </div>
+                                                        <tr class=desktop_class>
+                                                            <Show when=move || selection.is_enabled()>
+                                                                <td class="w-0">{selection_cell}</td>
+                                                            </Show>
 
-                                                        // =================== MOBILE VIEW ===================
+                                                            {move || {
+                                                                headers
+                                                                    .get()
+                                                                    .into_iter()
+                                                                    .map(|header| {
+                                                                        let align = if header.kind.is_numeric() { "text-right" } else { "" };
+                                                                        let priority = column_class.with_value(|column_class| column_class(header.priority));
+                                                                        view! {
+                                                                            <td class=theme.with_value(|theme| format!("{} {} {}", theme.cell, align, priority))>
+                                                                                <TableCell header=header row=value_clone.clone()/>
+                                                                            </td>
+                                                                        }
+                                                                    })
+                                                                    .collect_view()
+                                                            }}
 
-                                                        <tr class = { move || if index % 2 != 0 {"px924:hidden bg-base-300"} else{"px924:hidden"}}>
-                                                            <td colspan = format!("{}", header_length)>
-                                                            {
-                                                                view! {
-                                                                    <button class = "flex justify-start w-full gap-2 border-l-2 rounded-none btn btn-ghost btn-md bg-base-100 border-l-success" on:click = move |_| hide_per_cell.update(|c| *c = !*c) >
-                                                                        <div class = "text-xs">
-                                                                            {&header_display}
-                                                                        </div>
-                                                                        <div class = "flex justify-end flex-1">
-                                                                            <Show when = move || hide_per_cell.get() >
-                                                                                <svg
-                                                                                    xmlns="http://www.w3.org/2000/svg"
-                                                                                    viewBox="0 0 20 20"
-                                                                                    fill="currentColor"
-                                                                                    class="w-4 h-4"
-                                                                                >
-                                                                                    <path
-                                                                                        fill-rule="evenodd"
-                                                                                        d="M5.23 7.21a.75.75 0 011.06.02L10 11.168l3.71-3.938a.75.75 0 111.08 1.04l-4.25 4.5a.75.75 0 01-1.08 0l-4.25-4.5a.75.75 0 01.02-1.06z"
-                                                                                        clip-rule="evenodd"
-                                                                                    ></path>
-                                                                                </svg>
-                                                                            </Show>
-                                                                        </div>
-                                                                    </button>
-                                                                }.into_view()
-                                                            }
-                                                            </td>
                                                         </tr>
 
-                                                        <TableRowMobile 
-                                                            headers 
-                                                            hide_per_cell 
-                                                            value
-                                                            index 
-                                                            header_length
-                                                        />
+                                                        // =================== MOBILE VIEW ===================
+                                                        {match mobile_layout {
+                                                            MobileLayout::Scroll | MobileLayout::Priority { .. } => view! {}.into_view(),
+                                                            MobileLayout::Cards => view! {
+                                                                <TableCardMobile
+                                                                    headers
+                                                                    value
+                                                                    header_length
+                                                                    class=mobile_class
+                                                                    selection_cell=move || view! {
+                                                                        <Show when=move || selection.is_enabled()>
+                                                                            {selection_cell}
+                                                                        </Show>
+                                                                    }
+                                                                />
+                                                            }.into_view(),
+                                                            MobileLayout::Accordion => view! {
+                                                                <tr class=mobile_class>
+                                                                    <td colspan = format!("{}", header_length)>
+                                                                    <div class = "flex items-center gap-2">
+                                                                    <Show when=move || selection.is_enabled()>
+                                                                        {selection_cell}
+                                                                    </Show>
+                                                                    {
+                                                                        view! {
+                                                                            <button class = mobile_toggle on:click = move |_| hide_per_cell.update(|c| *c = !*c) >
+                                                                                <div class = "text-xs">
+                                                                                    {&header_display}
+                                                                                </div>
+                                                                                <div class = "flex justify-end flex-1">
+                                                                                    <Show when = move || hide_per_cell.get() >
+                                                                                        <svg
+                                                                                            xmlns="http://www.w3.org/2000/svg"
+                                                                                            viewBox="0 0 20 20"
+                                                                                            fill="currentColor"
+                                                                                            class="w-4 h-4"
+                                                                                        >
+                                                                                            <path
+                                                                                                fill-rule="evenodd"
+                                                                                                d="M5.23 7.21a.75.75 0 011.06.02L10 11.168l3.71-3.938a.75.75 0 111.08 1.04l-4.25 4.5a.75.75 0 01-1.08 0l-4.25-4.5a.75.75 0 01.02-1.06z"
+                                                                                                clip-rule="evenodd"
+                                                                                            ></path>
+                                                                                        </svg>
+                                                                                    </Show>
+                                                                                </div>
+                                                                            </button>
+                                                                        }.into_view()
+                                                                    }
+                                                                    </div>
+                                                                    </td>
+                                                                </tr>
+
+                                                                <TableRowMobile 
+                                                                    headers 
+                                                                    hide_per_cell 
+                                                                    value
+                                                                    index 
+                                                                    header_length
+                                                                    row_class
+                                                                    mobile_only=mobile_only.get_value()
+                                                                />
+                                                            }.into_view(),
+                                                        }}
                                                     }
                                                 })
                                                 .collect_view()
@@ -306,62 +495,497 @@ pub fn DataTable(
                 </tbody>
                 <tfoot>
                     <tr>
-                        <td colspan=move || headers.get().len()>
-                            <TablePagination
-                                total=total
-                                limit=limit
-                                offset=offset
-                                current_page=current_page
-                            />
+                        <td colspan=column_count>
+                            <TablePagination state=state/>
                         </td>
                     </tr>
                 </tfoot>
             </table>
+            </div>
         </div>
     }
 }
 
+/// Data table component that drives a `DataSource` on its own
+///
+/// The count and the page are fetched whenever the search, sort, page or filter change, with a common loading and error state.
+/// The download file is only fetched when the download button is clicked.
+///
+/// # Arguments
+///
+/// * `headers` - headers with extra data for the table
+/// * `source` - source of the rows, see `DataSource`
+/// * `state` - pagination, sort, search and filter state of the table (default 10 rows per page)
+/// * `fields` - fields passed to the source on download
+/// * `table_name` - table name passed to the source on download
+/// * `allow_download` - allow download of the table data
+/// * `download_filename` - filename for the downloaded file
+/// * `key_to_display_in_mobile` - key to display in mobile view header
+/// * `selection` - selection of rows with a checkbox column, hidden by default, see `RowSelection`
+/// * `bulk_actions` - actions on the selected rows, shown in the toolbar when rows are selected
+/// * `toolbar` - extra controls shown in the toolbar, next to the download button
+/// * `row_styles` - ordered style rules of the rows, see `DataTable`
+/// * `row_class` - callback returning extra classes of a row, see `DataTable`
+/// * `theme` - classes of the parts of the table, see `TableTheme`
+/// * `breakpoint` - screen width where the table switches to the mobile layout, see `Breakpoint`
+/// * `mobile_layout` - how the rows are displayed below the breakpoint, see `MobileLayout`
+/// * `export_formats` - formats of the download picked in the toolbar, requested from `fetch_export`, see `DataTable`
+/// * `export_scopes` - rows of the download picked in the toolbar, see `DataTable`
+/// * `csv_options` - options of the file built from the rows of the current page or the selection, see `CsvOptions`
+#[allow(non_snake_case)]
+#[component]
+pub fn DataSourceTable<S: DataSource>(
+    headers: RwSignal<Vec<TableHeader>>,
+    source: S,
+    #[prop(optional)] state: TableState,
+    #[prop(optional)] fields: RwSignal<String>,
+    #[prop(optional, into)] table_name: String,
+    #[prop(optional)] allow_download: RwSignal<bool>,
+    #[prop(optional)] download_filename: RwSignal<String>,
+    #[prop(optional)] key_to_display_in_mobile: RwSignal<String>,
+    #[prop(optional)] selection: RowSelection,
+    #[prop(optional)] bulk_actions: Vec<BulkAction>,
+    #[prop(optional, into)] toolbar: ViewFn,
+    #[prop(optional)] row_styles: Vec<StyleRule>,
+    #[prop(optional, into)] row_class: Option<Callback<Value, String>>,
+    #[prop(optional)] theme: Option<TableTheme>,
+    #[prop(optional)] breakpoint: Breakpoint,
+    #[prop(optional)] mobile_layout: MobileLayout,
+    #[prop(optional)] export_formats: Vec<ExportFormat>,
+    #[prop(optional)] export_scopes: Vec<ExportScope>,
+    #[prop(optional)] csv_options: CsvOptions,
+) -> impl IntoView {
+    if let Some(theme) = theme {
+        provide_context(theme);
+    }
+    let theme = TableTheme::from_context();
+    let source = Rc::new(source);
+    let row_class = Callback::new(move |row: Value| row_class.map(|row_class| row_class.call(row)).unwrap_or_default());
+    let data = RwSignal::new(Vec::<Value>::new());
+
+    let query = Signal::derive(move || state.query());
+    // The count only depends on the search and the filters
+    let count_query = Signal::derive(move || TableQuery {
+        search: state.search(),
+        filter: state.filter(),
+        filters: state.filters(),
+        condition: state.condition(),
+        ..Default::default()
+    });
+
+    let count_source = source.clone();
+    let count_resource = create_local_resource(move || count_query.get(), move |q| {
+        let source = count_source.clone();
+        async move { source.fetch_count(q).await }
+    });
+    let page_source = source.clone();
+    let page_resource = create_local_resource(move || query.get(), move |q| {
+        let source = page_source.clone();
+        async move { source.fetch_page(q).await }
+    });
+    // The download file is only fetched when the download button is clicked
+    let export = create_action(move |d: &DownloadDataRequest| {
+        let source = source.clone();
+        let d = d.clone();
+        async move { source.fetch_export(d).await }
+    });
+    // The table is rendered once, the fetched count and rows are written to the state and the data
+    create_effect(move |_| {
+        if let Some(Ok(count)) = count_resource.get() {
+            state.set_total(count);
+        }
+    });
+    create_effect(move |_| {
+        if let Some(Ok(rows)) = page_resource.get() {
+            data.set(rows);
+        }
+    });
+    let loading = move || count_resource.loading().get() || page_resource.loading().get();
+    let failed = move || {
+        count_resource.with(|count| matches!(count, Some(Err(_)))) || page_resource.with(|rows| matches!(rows, Some(Err(_))))
+    };
+
+    view! {
+        <Show when=loading>
+            <div class = "flex justify-center gap-2 items-center p-4">
+                <span class = theme.spinner.clone()></span>
+                <span class = "text-xs opacity-50 font-extralight">"Loading Data"</span>
+            </div>
+        </Show>
+        <Show when=move || failed() && !loading()>
+            <div class = "flex justify-center gap-2 items-center p-4">
+                <span class = format!("text-xs opacity-50 font-extralight {}", theme.error_text)>"Error Loading Data"</span>
+            </div>
+        </Show>
+        <DataTable
+            headers = headers
+            data = data.into()
+            state = state
+            allow_download = allow_download
+            download_filename = download_filename
+            export = export
+            table_name = table_name
+            fields = fields
+            key_to_display_in_mobile = key_to_display_in_mobile
+            selection = selection
+            bulk_actions = bulk_actions
+            toolbar = toolbar
+            row_styles = row_styles
+            row_class = row_class
+            breakpoint = breakpoint
+            mobile_layout = mobile_layout
+            export_formats = export_formats
+            export_scopes = export_scopes
+            csv_options = csv_options
+        />
+    }
+}
+
+/// Selected rows counter and bulk action buttons, destructive actions ask for confirmation first
 #[allow(non_snake_case)]
 #[component]
-fn TableHeader(sort_by: RwSignal<String>, header: RwSignal<TableHeader>, sort: RwSignal<bool>) -> impl IntoView {
+fn BulkActionBar(selection: RowSelection, actions: Vec<BulkAction>, state: TableState) -> impl IntoView {
+    let actions = store_value(actions);
+    let pending = RwSignal::new(None::<usize>);
+    let run = move |index: usize| {
+        let action = actions.with_value(|actions| actions[index].on_click);
+        let rows = untrack(|| match selection.is_all_pages() {
+            true => BulkSelection::AllMatching {
+                query: state.query(),
+                excluded: selection.excluded_keys(),
+            },
+            false => BulkSelection::Rows(selection.selected_rows()),
+        });
+        action.call(rows);
+    };
+    let selected_count = move || selection.count(state.total());
+    let has_selected = move || selection.is_enabled() && selected_count() > 0;
+    let theme = store_value(TableTheme::from_context());
+    view! {
+        <Show when=has_selected>
+            <div class="flex gap-1 items-center">
+                <span class=theme.with_value(|theme| theme.selection_badge.clone())>{move || format!("{} selected", selected_count())}</span>
+                {actions.with_value(|actions| {
+                    actions
+                        .iter()
+                        .enumerate()
+                        .map(|(index, action)| {
+                            let destructive = action.confirm.is_some();
+                            view! {
+                                <button
+                                    class=theme.with_value(|theme| match destructive {
+                                        true => format!("{} {}", theme.bulk_button, theme.error_text),
+                                        false => theme.bulk_button.clone(),
+                                    })
+                                    on:click=move |_| match destructive {
+                                        true => pending.set(Some(index)),
+                                        false => run(index),
+                                    }
+                                >
+                                    <div class="flex gap-2 justify-normal text-center items-center content-center">
+                                        {action.icon.clone().map(|icon| view! {
+                                            <svg
+                                                xmlns="http://www.w3.org/2000/svg"
+                                                viewBox="0 0 20 20"
+                                                fill="currentColor"
+                                                class="w-3 h-3"
+                                            >
+                                                <path fill-rule="evenodd" d=icon clip-rule="evenodd"></path>
+                                            </svg>
+                                        })}
+                                        <span class="font-extralight">{action.label.clone()}</span>
+                                    </div>
+                                </button>
+                            }
+                        })
+                        .collect_view()
+                })}
+            </div>
+        </Show>
+        <Show when=move || pending.get().is_some()>
+            <div class=theme.with_value(|theme| theme.modal.clone())>
+                <div class=theme.with_value(|theme| theme.modal_box.clone())>
+                    <p class="text-sm">
+                        {move || pending.get().and_then(|index| actions.with_value(|actions| actions[index].confirm.clone()))}
+                    </p>
+                    <p class="text-xs opacity-50 font-extralight">{move || format!("{} selected", selected_count())}</p>
+                    <div class=theme.with_value(|theme| theme.modal_action.clone())>
+                        <button class=theme.with_value(|theme| theme.cancel_button.clone()) on:click=move |_| pending.set(None)>"Cancel"</button>
+                        <button
+                            class=theme.with_value(|theme| theme.confirm_button.clone())
+                            on:click=move |_| {
+                                if let Some(index) = pending.get_untracked() {
+                                    pending.set(None);
+                                    run(index);
+                                }
+                            }
+                        >
+                            "Confirm"
+                        </button>
+                    </div>
+                </div>
+            </div>
+        </Show>
+    }
+}
+
+/// Header cell, click to cycle the sort ascending -> descending -> not sorted, shift click to add a secondary sort
+#[allow(non_snake_case)]
+#[component]
+fn TableHeader(state: TableState, header: RwSignal<TableHeader>, #[prop(optional, into)] class: String) -> impl IntoView {
+    let sorted = move || state.sort_of(&header.get().sort_name);
+    let show_priority = move || state.sort().len() > 1;
     view! {
         <th
-            class="cursor-pointer text-sm text-white bg-opacity-50 bg-success uppercase"
-            on:click=move |_| {
-                sort.update(|i| *i = !*i);
-                sort_by.set(header.get().sort_name);
+            class=format!("{} {}", TableTheme::from_context().header_cell, class)
+            on:click=move |e| {
+                let sort_name = header.get().sort_name;
+                if e.shift_key() {
+                    state.toggle_sort_multi(&sort_name)
+                } else {
+                    state.toggle_sort(&sort_name)
+                }
             }
         >
             <div class="flex justify-between">
                 <span class="flex-0">{move || header.get().display_name}</span>
-                <span class="flex-0">
-                    <svg
-                        xmlns="http://www.w3.org/2000/svg"
-                        viewBox="0 0 20 20"
-                        fill="currentColor"
-                        class="w-5 h-5"
-                    >
-                        <path
-                            fill-rule="evenodd"
-                            d="M10 3a.75.75 0 01.55.24l3.25 3.5a.75.75 0 11-1.1 1.02L10 4.852 7.3 7.76a.75.75 0 01-1.1-1.02l3.25-3.5A.75.75 0 0110 3zm-3.76 9.2a.75.75 0 011.06.04l2.7 2.908 2.7-2.908a.75.75 0 111.1 1.02l-3.25 3.5a.75.75 0 01-1.1 0l-3.25-3.5a.75.75 0 01.04-1.06z"
-                            clip-rule="evenodd"
-                        ></path>
-                    </svg>
+                <span class="flex-0 flex items-center">
+                    {move || match sorted() {
+                        Some((SortDirection::Ascending, _)) => view! {
+                            <svg
+                                xmlns="http://www.w3.org/2000/svg"
+                                viewBox="0 0 20 20"
+                                fill="currentColor"
+                                class="w-5 h-5"
+                            >
+                                <path
+                                    fill-rule="evenodd"
+                                    d="M14.77 12.79a.75.75 0 01-1.06-.02L10 8.832 6.29 12.77a.75.75 0 11-1.08-1.04l4.25-4.5a.75.75 0 011.08 0l4.25 4.5a.75.75 0 01-.02 1.06z"
+                                    clip-rule="evenodd"
+                                ></path>
+                            </svg>
+                        }.into_view(),
+                        Some((SortDirection::Descending, _)) => view! {
+                            <svg
+                                xmlns="http://www.w3.org/2000/svg"
+                                viewBox="0 0 20 20"
+                                fill="currentColor"
+                                class="w-5 h-5"
+                            >
+                                <path
+                                    fill-rule="evenodd"
+                                    d="M5.23 7.21a.75.75 0 011.06.02L10 11.168l3.71-3.938a.75.75 0 111.08 1.04l-4.25 4.5a.75.75 0 01-1.08 0l-4.25-4.5a.75.75 0 01.02-1.06z"
+                                    clip-rule="evenodd"
+                                ></path>
+                            </svg>
+                        }.into_view(),
+                        None => view! {
+                            <svg
+                                xmlns="http://www.w3.org/2000/svg"
+                                viewBox="0 0 20 20"
+                                fill="currentColor"
+                                class="w-5 h-5 opacity-30"
+                            >
+                                <path
+                                    fill-rule="evenodd"
+                                    d="M10 3a.75.75 0 01.55.24l3.25 3.5a.75.75 0 11-1.1 1.02L10 4.852 7.3 7.76a.75.75 0 01-1.1-1.02l3.25-3.5A.75.75 0 0110 3zm-3.76 9.2a.75.75 0 011.06.04l2.7 2.908 2.7-2.908a.75.75 0 111.1 1.02l-3.25 3.5a.75.75 0 01-1.1 0l-3.25-3.5a.75.75 0 01.04-1.06z"
+                                    clip-rule="evenodd"
+                                ></path>
+                            </svg>
+                        }.into_view(),
+                    }}
+                    <Show when=move || show_priority() && sorted().is_some()>
+                        <span class="text-xs font-light">{move || sorted().map(|(_, priority)| priority)}</span>
+                    </Show>
                 </span>
             </div>
         </th>
     }
 }
 
+/// Filter cell under the header, rendered from the `filter` of the header
+#[allow(non_snake_case)]
+#[component]
+fn TableFilterCell(state: TableState, header: TableHeader, #[prop(optional, into)] class: String) -> impl IntoView {
+    view! {
+        <th class=format!("{} {}", TableTheme::from_context().filter_cell, class)>
+            <TableFilterInput state=state header=header/>
+        </th>
+    }
+}
+
+/// The filters of the columns stacked in a row, for the mobile layouts without the filter row
+#[allow(non_snake_case)]
+#[component]
+fn TableFiltersMobile(state: TableState, headers: RwSignal<Vec<TableHeader>>, colspan: Signal<usize>, class: String) -> impl IntoView {
+    let theme = TableTheme::from_context();
+    let label_class = store_value(theme.mobile_label);
+    view! {
+        <tr class=format!("{} {}", class, theme.filter_cell)>
+            <td colspan=colspan>
+                <div class="flex flex-col gap-1">
+                    {move || {
+                        headers
+                            .get()
+                            .into_iter()
+                            .filter(|header| header.filter.is_some())
+                            .map(|header| view! {
+                                <label class="flex items-center gap-2">
+                                    <span class=format!("{} w-1/3 shrink-0", label_class.get_value())>{header.display_name.clone()}</span>
+                                    <div class="flex-auto">
+                                        <TableFilterInput state=state header=header/>
+                                    </div>
+                                </label>
+                            })
+                            .collect_view()
+                    }}
+                    <Show when=move || !state.filters().is_empty()>
+                        <button class=theme.pagination_button.clone() on:click=move |_| state.clear_column_filters()>"Clear filters"</button>
+                    </Show>
+                </div>
+            </td>
+        </tr>
+    }
+}
+
+/// The input of the filter of a column, by its `FilterKind`
+#[allow(non_snake_case)]
+#[component]
+fn TableFilterInput(state: TableState, header: TableHeader) -> impl IntoView {
+    let field = store_value(header.sort_name.clone());
+    let current = create_memo(move |_| field.with_value(|field| state.filter_of(field)));
+    let set = move |value: Option<ColumnFilterValue>| field.with_value(|field| state.set_column_filter(field, value));
+    let theme = TableTheme::from_context();
+    let input_class = store_value(format!("{} w-full", theme.search_input));
+    let select_class = store_value(format!("{} w-full", theme.select));
+    let text_value = move || match current.get() {
+        Some(ColumnFilterValue::Contains(text)) | Some(ColumnFilterValue::Equals(text)) => text,
+        _ => String::new(),
+    };
+    let number_range = move || match current.get() {
+        Some(ColumnFilterValue::NumberRange { min, max }) => (min, max),
+        _ => (None, None),
+    };
+    let date_range = move || match current.get() {
+        Some(ColumnFilterValue::DateRange { from, to }) => (from, to),
+        _ => (None, None),
+    };
+    let non_empty = |value: String| match value.trim().is_empty() {
+        true => None,
+        false => Some(value.trim().to_string()),
+    };
+    let set_number_range = move |min: Option<f64>, max: Option<f64>| match (min, max) {
+        (None, None) => set(None),
+        (min, max) => set(Some(ColumnFilterValue::NumberRange { min, max })),
+    };
+    let set_date_range = move |from: Option<String>, to: Option<String>| match (&from, &to) {
+        (None, None) => set(None),
+        _ => set(Some(ColumnFilterValue::DateRange { from, to })),
+    };
+    view! {
+        {match header.filter {
+                None => view! {}.into_view(),
+                Some(FilterKind::Contains) => view! {
+                    <input
+                        type="text"
+                        class=input_class.get_value()
+                        placeholder="Contains"
+                        prop:value=text_value
+                        on:change=move |e| set(non_empty(event_target_value(&e)).map(ColumnFilterValue::Contains))
+                    />
+                }.into_view(),
+                Some(FilterKind::Equals) => view! {
+                    <input
+                        type="text"
+                        class=input_class.get_value()
+                        placeholder="Equals"
+                        prop:value=text_value
+                        on:change=move |e| set(non_empty(event_target_value(&e)).map(ColumnFilterValue::Equals))
+                    />
+                }.into_view(),
+                Some(FilterKind::NumberRange) => view! {
+                    <div class="flex gap-1">
+                        <input
+                            type="number"
+                            class=input_class.get_value()
+                            placeholder="Min"
+                            prop:value=move || number_range().0.map(|n| n.to_string()).unwrap_or_default()
+                            on:change=move |e| set_number_range(event_target_value(&e).parse::<f64>().ok(), untrack(number_range).1)
+                        />
+                        <input
+                            type="number"
+                            class=input_class.get_value()
+                            placeholder="Max"
+                            prop:value=move || number_range().1.map(|n| n.to_string()).unwrap_or_default()
+                            on:change=move |e| set_number_range(untrack(number_range).0, event_target_value(&e).parse::<f64>().ok())
+                        />
+                    </div>
+                }.into_view(),
+                Some(FilterKind::DateRange) => view! {
+                    <div class="flex gap-1">
+                        <input
+                            type="date"
+                            class=input_class.get_value()
+                            prop:value=move || date_range().0.unwrap_or_default()
+                            on:change=move |e| set_date_range(non_empty(event_target_value(&e)), untrack(date_range).1)
+                        />
+                        <input
+                            type="date"
+                            class=input_class.get_value()
+                            prop:value=move || date_range().1.unwrap_or_default()
+                            on:change=move |e| set_date_range(untrack(date_range).0, non_empty(event_target_value(&e)))
+                        />
+                    </div>
+                }.into_view(),
+                Some(FilterKind::Select(options)) => view! {
+                    <select
+                        class=select_class.get_value()
+                        on:change=move |e| set(non_empty(event_target_value(&e)).map(ColumnFilterValue::Equals))
+                    >
+                        <option value="" prop:selected=move || text_value().is_empty()>"All"</option>
+                        {options
+                            .into_iter()
+                            .map(|option| {
+                                let selected = option.clone();
+                                view! {
+                                    <option
+                                        prop:selected=move || text_value() == selected
+                                        value=option.clone()
+                                    >
+                                        {option.clone()}
+                                    </option>
+                                }
+                            })
+                            .collect_view()}
+                    </select>
+                }.into_view(),
+                Some(FilterKind::Boolean) => view! {
+                    <select
+                        class=select_class.get_value()
+                        on:change=move |e| set(event_target_value(&e).parse::<bool>().ok().map(ColumnFilterValue::Boolean))
+                    >
+                        <option value="" prop:selected=move || current.get().is_none()>"All"</option>
+                        <option value="true" prop:selected=move || current.get() == Some(ColumnFilterValue::Boolean(true))>"True"</option>
+                        <option value="false" prop:selected=move || current.get() == Some(ColumnFilterValue::Boolean(false))>"False"</option>
+                    </select>
+                }.into_view(),
+            }}
+    }
+}
+
 #[allow(non_snake_case)]
 #[component]
-fn TableRow(sort_by: RwSignal<String>, header: RwSignal<TableHeader>, sort: RwSignal<bool>) -> impl IntoView {
+fn TableRow(state: TableState, header: RwSignal<TableHeader>) -> impl IntoView {
     view! {
         <th
             class="cursor-pointer"
-            on:click=move |_| {
-                sort.update(|i| *i = !*i);
-                sort_by.set(header.get().sort_name);
+            on:click=move |e| {
+                let sort_name = header.get().sort_name;
+                if e.shift_key() {
+                    state.toggle_sort_multi(&sort_name)
+                } else {
+                    state.toggle_sort(&sort_name)
+                }
             }
         >
             {move || header.get().display_name}
@@ -371,7 +995,10 @@ fn TableRow(sort_by: RwSignal<String>, header: RwSignal<TableHeader>, sort: RwSi
 
 #[allow(non_snake_case)]
 #[component]
-fn TableRowMobile(headers: RwSignal<Vec<TableHeader>>, hide_per_cell: RwSignal<bool>, value: Value, index: usize, header_length: usize) -> impl IntoView {
+fn TableRowMobile(headers: RwSignal<Vec<TableHeader>>, hide_per_cell: RwSignal<bool>, value: Value, index: usize, header_length: usize, #[prop(optional, into)] row_class: String, mobile_only: String) -> impl IntoView {
+    let theme = TableTheme::from_context();
+    let class = format!("{} {} {}", mobile_only, theme.row_class(index), row_class);
+    let label_class = theme.mobile_label;
     view! {
         {move || {
             headers
@@ -379,73 +1006,10 @@ fn TableRowMobile(headers: RwSignal<Vec<TableHeader>>, hide_per_cell: RwSignal<b
             .into_iter()
             .map(|header| {
                 view! {
-                    <tr prop:hidden = move || hide_per_cell.get() class = { move || if index % 2 != 0 {"uppercase hover:opacity-50 bg-base-300"} else{"uppercase hover:opacity-50"}}>
-                        <td colspan = {format!("{}", header_length)} class="px924:hidden">
-                            {
-                                match header.prefix {
-                                    Some(ref p) => view! { <span class="text-xs opacity-50 text-xs/3">{format!("{} ", p)}</span> }.into_view(),
-                                    None => view! {}.into_view()
-                                }
-                            }
-                            {
-                                let number_style = if header.is_number_styled {
-                                    match header
-                                    .find(&value)
-                                    .parse::<f64>()
-                                    .ok()
-                                    {
-                                        Some(parsed_value) if parsed_value >= 0.0 => "text-success",
-                                        Some(_) => "text-error",
-                                        None => "",
-                                    }
-                                }else {""};
-                                let style_when_success = match header
-                                    .find(&value)
-                                    .to_uppercase().contains(&header.style_when_success.to_uppercase()) && !header.style_when_success.is_empty()
-                                {
-                                    true => "text-success",
-                                    false => "",
-                                };
-                                let style_when_error = match header
-                                    .find(&value)
-                                    .to_uppercase().contains(&header.style_when_error.to_uppercase()) && !header.style_when_error.is_empty()
-                                {
-                                    true => "text-error",
-                                    false => "",
-                                };
-                                let case_style = match header.to_uppercase {
-                                    true => "uppercase",
-                                    false => "",
-                                };
-                                let style = format!(
-                                    "px924:hidden {} {} {} {}",
-                                    number_style,
-                                    style_when_success,
-                                    style_when_error,
-                                    case_style,
-                                );
-                                view! { 
-                                    <span class="mr-2 text-sm text-success px924:hidden">{ header.display_name.clone() }: </span>
-                                    <span class=style>{header.find(&value)}</span> 
-                                }
-                            }
-                            {match header.is_currency {
-                                true => {
-                                    let has_value = header.find(&value).parse::<f64>().is_ok();
-                                    if has_value {
-                                        view! {
-                                            <span class="px924:hidden text-xs opacity-50 text-xs/3">
-                                                {format!(" {}", header.find_currency(&value))}
-                                            </span>
-                                        }
-                                    }else{
-                                        view! { <span></span> }
-                                    }
-                                    
-                                }
-                                false => view! { <span></span> },
-                            }}
-
+                    <tr prop:hidden = move || hide_per_cell.get() class = class.clone()>
+                        <td colspan = {format!("{}", header_length)}>
+                            <span class=label_class.clone()>{ header.display_name.clone() }: </span>
+                            <TableCell header row=value.clone()/>
                         </td>
                     </tr>
                 }
@@ -455,75 +1019,158 @@ fn TableRowMobile(headers: RwSignal<Vec<TableHeader>>, hide_per_cell: RwSignal<b
     }
 }
 
+/// A record of the mobile view as a card with every column stacked
+#[allow(non_snake_case)]
+#[component]
+fn TableCardMobile(headers: RwSignal<Vec<TableHeader>>, value: Value, header_length: usize, class: String, #[prop(into)] selection_cell: ViewFn) -> impl IntoView {
+    let label_class = TableTheme::from_context().mobile_label;
+    view! {
+        <tr class=class>
+            <td colspan = {format!("{}", header_length)}>
+                <div class="flex gap-2 items-start">
+                    {selection_cell.run()}
+                    <div class="flex flex-col flex-1 gap-1 py-1">
+                        {move || {
+                            headers
+                                .get()
+                                .into_iter()
+                                .map(|header| view! {
+                                    <div class="flex justify-between gap-2">
+                                        <span class=label_class.clone()>{header.display_name.clone()}</span>
+                                        <span class="text-right"><TableCell header row=value.clone()/></span>
+                                    </div>
+                                })
+                                .collect_view()
+                        }}
+                    </div>
+                </div>
+            </td>
+        </tr>
+    }
+}
+
+/// Content of a cell, rendered by the `renderer` of the header, or as the styled value with its prefix and currency
+#[allow(non_snake_case)]
+#[component]
+fn TableCell(header: TableHeader, row: Value, #[prop(optional)] class: &'static str) -> impl IntoView {
+    let raw = header.find(&row);
+    let value = header.display_value(&row);
+    let theme = TableTheme::from_context();
+    match header.renderer {
+        Some(CellRenderer::Link { ref href_field, new_tab }) => {
+            let href = match href_field {
+                Some(field) => TableHeader { name: field.clone(), ..Default::default() }.find(&row),
+                None => raw,
+            };
+            match is_safe_href(&href) {
+                true => view! {
+                    <a class=format!("{} {}", theme.link_cell, class) href=href target=new_tab.then_some("_blank")>{value}</a>
+                }.into_view(),
+                false => view! { <span class=class>{value}</span> }.into_view(),
+            }
+        }
+        Some(CellRenderer::Badge { class: ref badge_class }) => view! {
+            <span class=format!("{} {} {}", theme.badge_cell, badge_class, class)>{value}</span>
+        }.into_view(),
+        Some(CellRenderer::Image { class: ref image_class }) => view! {
+            <img class=format!("{} {}", image_class, class) src=raw alt=header.display_name.clone()/>
+        }.into_view(),
+        Some(CellRenderer::Button { ref label, on_click }) => {
+            let label = label.clone().unwrap_or(value);
+            view! {
+                <button class=format!("{} {}", theme.button_cell, class) on:click=move |_| on_click.call(row.clone())>{label}</button>
+            }.into_view()
+        }
+        Some(CellRenderer::Custom(ref render)) => render(&row),
+        None => {
+            let rule_style = header.cell_class(&row, &theme);
+            let case_style = match header.to_uppercase {
+                true => "uppercase",
+                false => "",
+            };
+            let style = format!("{} {} {}", class, rule_style, case_style);
+            let currency = match header.has_currency() && header.currency_format.is_none() && raw.parse::<f64>().is_ok() {
+                true => Some(header.find_currency(&row)),
+                false => None,
+            };
+            let null_style = match header.null_value.is_some() && header.is_null(&row) {
+                true => "italic opacity-50",
+                false => "",
+            };
+            let content = match (&header.array_display, &header.object_display, resolve_path(&row, &header.name)) {
+                (ArrayDisplay::Chips, _, Some(Value::Array(_))) => {
+                    let items = header.find_items(&row).unwrap_or_default();
+                    match items.is_empty() {
+                        true => value.into_view(),
+                        false => items
+                            .into_iter()
+                            .map(|item| view! { <span class=theme.chip.clone()>{item}</span> })
+                            .collect_view(),
+                    }
+                }
+                (_, ObjectDisplay::Json, Some(object @ Value::Object(_))) => {
+                    let pretty = serde_json::to_string_pretty(object).unwrap_or_default();
+                    view! {
+                        <details class="normal-case">
+                            <summary class="cursor-pointer truncate max-w-xs">{value}</summary>
+                            <pre class="text-xs text-left whitespace-pre-wrap">{pretty}</pre>
+                        </details>
+                    }.into_view()
+                }
+                _ => value.into_view(),
+            };
+            view! {
+                {header.prefix.clone().map(|p| view! { <span class=format!("{} text-xs opacity-50 text-xs/3", class)>{format!("{} ", p)}</span> })}
+                <span class=format!("{} {}", style, null_style)>{content}</span>
+                {currency.map(|c| view! { <span class=format!("{} text-xs opacity-50 text-xs/3", class)>{format!(" {}", c)}</span> })}
+            }.into_view()
+        }
+    }
+}
+
 /// Pagination component for the DirectusDataTable
 /// 
 /// # Arguments
 /// 
-/// * `total` - Total number of rows in the table
-/// * `limit` - Number of rows to display per page
-/// * `offset` - The current offset of the table
-/// * `current_page` - The current page number
+/// * `state` - pagination state of the table, see `TableState`
+///
+/// The buttons are styled by the `TableTheme` of the context.
 #[allow(non_snake_case)]
 #[component]
 pub fn TablePagination(
-    total: RwSignal<u32>,
-    limit: RwSignal<u32>,
-    offset: RwSignal<u32>,
-    current_page: RwSignal<u32>
+    state: TableState,
 ) -> impl IntoView {
-    let previous_disabled = move || current_page.get() == 1;
-    let aggregated_button = move || {
-        // let mut buttons = vec![];
-        let page_count = total.get() / limit.get();
-        let total_page = if total.get() % limit.get() != 0 {page_count + 1} else {page_count};
-        generate_button_numbers(current_page.get(), total_page)
-        // for i in 1..=total_page {
-        //     buttons.push(i);
-        // }
-        // if total.get() % limit.get() != 0 {
-        //     buttons.push(total_page + 1);
-        // }
-        // buttons
-    };
-    let total_page_count = move || {
-        let page_count = total.get() / limit.get();
-        if total.get() % limit.get() != 0 {
-            page_count + 1
-        } else {
-            page_count
-        }
-    };
-    let row_from = move || offset.get() + 1;
+    let previous_disabled = move || state.page() == 1;
+    let aggregated_button = move || generate_button_numbers(state.page(), state.page_count());
+    let row_from = move || state.offset() + 1;
     let row_to = move || {
-        let r = row_from() + limit.get() - 1;
-        if r > total.get() {
-            total.get()
+        let r = row_from() + state.limit() - 1;
+        if r > state.total() {
+            state.total()
         } else {
             r
         }
     };
 
-    let show_pagination = move || limit.get() < total.get();
-    let next_disabled = move || current_page.get() == total_page_count();
+    let show_pagination = move || state.limit() < state.total();
+    let next_disabled = move || state.page() == state.page_count();
+    let theme = store_value(TableTheme::from_context());
     
     view! {
         <div class="flex justify-between w-full">
             <div class="flex-auto">
                 <span>
                     {move || {
-                        format!("Showing {} to {} of {} entries", row_from(), row_to(), total.get())
+                        format!("Showing {} to {} of {} entries", row_from(), row_to(), state.total())
                     }}
                 </span>
             </div>
             <Show when=move || show_pagination()>
                 <div class="flex flex-auto justify-end">
                     <button
-                        class="btn btn-ghost btn-xs"
-                        prop:disabled=move || previous_disabled()
-                        on:click=move |_| {
-                            current_page.update(|i| *i = *i - 1);
-                            offset.set((current_page.get() - 1) * limit.get());
-                        }
+                        class=theme.with_value(|theme| theme.pagination_button.clone())
+                        prop:disabled=previous_disabled
+                        on:click=move |_| state.previous_page()
                     >
 
                         Previous
@@ -535,12 +1182,9 @@ pub fn TablePagination(
                             .map(|i| {
                                 view! {
                                     <button
-                                        class="btn btn-square btn-xs"
-                                        prop:disabled=move || current_page.get() == i
-                                        on:click=move |_| {
-                                            current_page.set(i);
-                                            offset.set((current_page.get() - 1) * limit.get());
-                                        }
+                                        class=theme.with_value(|theme| theme.page_button.clone())
+                                        prop:disabled=move || state.page() == i
+                                        on:click=move |_| state.set_page(i)
                                     >
                                         {i}
                                     </button>
@@ -550,13 +1194,10 @@ pub fn TablePagination(
                     }}
 
                     <button
-                        class="btn btn-ghost btn-xs"
-                        on:click=move |_| {
-                            current_page.update(|i| *i = *i + 1);
-                            offset.set((current_page.get() - 1) * limit.get());
-                        }
+                        class=theme.with_value(|theme| theme.pagination_button.clone())
+                        on:click=move |_| state.next_page()
 
-                        prop:disabled=move || next_disabled()
+                        prop:disabled=next_disabled
                     >
                         Next
                     </button>
@@ -567,52 +1208,171 @@ pub fn TablePagination(
 }
 
 
+/// Button downloading a text content
+///
+/// # Arguments
+///
+/// * `content` - content of the file
+/// * `file_name` - name of the file, prefixed with the current local time
+/// * `format` - format of the content, for the MIME type and the extension of the file (default `ExportFormat::Csv`)
+/// * `button_name` - label of the button (default the label of the format)
 #[allow(non_snake_case)]
 #[component]
 pub fn DownloadCsvAnchor(
     content: String,
     file_name: String,
+    #[prop(optional)] format: ExportFormat,
+    #[prop(optional)] button_name: String,
+) -> impl IntoView {
+    let download = move || download_blob(content.as_bytes(), format.mime(), &timestamped_file_name(&file_name, format.extension()));
+    let label = Signal::derive(move || button_label(&button_name, format));
+    view! { <DownloadButton label=label on_click=download/> }
+}
+
+/// Button building a file from rows on click, for tables without a back end export
+///
+/// A format picker is shown next to the button when more than one format is given.
+///
+/// # Arguments
+///
+/// * `headers` - headers of the columns, `display_name` is the header row
+/// * `rows` - rows of the file
+/// * `file_name` - name of the file, prefixed with the current local time
+/// * `formats` - formats of the picker, the first is selected (default CSV only), see `ExportFormat`
+/// * `options` - raw or formatted values, byte order mark and delimiter, see `CsvOptions`
+/// * `button_name` - label of the button (default the label of the selected format)
+#[allow(non_snake_case)]
+#[component]
+pub fn DownloadRows(
+    #[prop(into)] headers: Signal<Vec<TableHeader>>,
+    #[prop(into)] rows: Signal<Vec<Value>>,
+    #[prop(into)] file_name: Signal<String>,
+    #[prop(optional)] formats: Vec<ExportFormat>,
+    #[prop(optional)] options: CsvOptions,
     #[prop(optional)] button_name: String,
 ) -> impl IntoView {
-    use wasm_bindgen::JsValue;
-    use web_sys::{
-        js_sys::{Array, Uint8Array},
-        Blob, BlobPropertyBag,
+    let formats = match formats.is_empty() {
+        true => vec![ExportFormat::Csv],
+        false => formats,
     };
-    let new_file_name = move || {
-        let utc = chrono::Utc::now();
-        let utc_local = utc.with_timezone(&chrono::Local);
-        let formatted_local = utc_local.format("%Y%m%d_%H%M%S").to_string();
-        format!("{}_{}.csv", formatted_local, file_name)
+    let selected = create_rw_signal(formats[0]);
+    let download = move || {
+        let format = selected.get_untracked();
+        let content = headers.with_untracked(|headers| rows.with_untracked(|rows| format.render(headers, rows, &options)));
+        download_blob(&content, format.mime(), &timestamped_file_name(&file_name.get_untracked(), format.extension()));
     };
-    let button_placeholder = move || match button_name.len() > 0 {
-        true => button_name,
-        false => String::from("CSV"),
+    let label = Signal::derive(move || button_label(&button_name, selected.get()));
+    view! {
+        <div class="flex justify-start gap-1 items-center">
+            <ExportPicker options=formats label=ExportFormat::label selected=selected/>
+            <DownloadButton label=label on_click=download/>
+        </div>
+    }
+}
+
+/// Button fetching a file with an action on click and downloading it once ready
+///
+/// A spinner is shown while the file is fetched, and the error next to the button when it fails.
+///
+/// # Arguments
+///
+/// * `export` - action producing the content of the file from the request
+/// * `request` - callback building the request when the button is clicked
+/// * `file_name` - name of the file, prefixed with the current local time
+/// * `format` - format requested from `export`, set on the request, for the MIME type and the extension of the file (default `ExportFormat::Csv`),
+///   the content of a binary format is base64 encoded
+/// * `button_name` - label of the button (default the label of the format)
+#[allow(non_snake_case)]
+#[component]
+pub fn DownloadExport(
+    export: Action<DownloadDataRequest, Result<String, ServerFnError>>,
+    #[prop(into)] request: Callback<(), DownloadDataRequest>,
+    #[prop(into)] file_name: Signal<String>,
+    #[prop(optional, into)] format: MaybeSignal<ExportFormat>,
+    #[prop(optional)] button_name: String,
+) -> impl IntoView {
+    // Only the files requested by this button are downloaded, in the format they were requested in
+    let requested = store_value(None::<ExportFormat>);
+    let invalid_content = create_rw_signal(false);
+    create_effect(move |_| {
+        if let Some(Ok(content)) = export.value().get() {
+            if let Some(format) = requested.get_value() {
+                requested.set_value(None);
+                let content = match format.is_binary() {
+                    true => export::decode_base64(&content),
+                    false => Some(content.into_bytes()),
+                };
+                match content {
+                    Some(content) => download_blob(&content, format.mime(), &timestamped_file_name(&file_name.get_untracked(), format.extension())),
+                    None => invalid_content.set(true),
+                }
+            }
+        }
+    });
+    let error = move || {
+        export.value().get().and_then(Result::err).map(|error| error.to_string())
+            .or_else(|| invalid_content.get().then(|| String::from("the file is not base64 encoded")))
+            .filter(|_| !export.pending().get())
     };
-    let download = move || {
-        let uint8arr = Uint8Array::new(&unsafe { Uint8Array::view(&content.as_bytes()) }.into());
-        let array = Array::new();
-        array.push(&uint8arr.buffer());
-        let file = Blob::new_with_u8_array_sequence_and_options(
-            &JsValue::from(array),
-            BlobPropertyBag::new().type_("text/csv"),
-        )
-        .unwrap();
-        let doc = leptos_dom::document();
-        let hyperlink = wasm_bindgen::JsCast::dyn_into::<web_sys::HtmlAnchorElement>(
-            doc.create_element("a").unwrap(),
-        )
-        .unwrap();
-        hyperlink.set_download(new_file_name().as_str());
-        let url = web_sys::Url::create_object_url_with_blob(&file).unwrap();
-        hyperlink.set_href(&url);
-        hyperlink.click();
-        hyperlink.remove();
+    let start = move || {
+        if !export.pending().get_untracked() {
+            let format = format.get_untracked();
+            requested.set_value(Some(format));
+            invalid_content.set(false);
+            export.dispatch(DownloadDataRequest { format, ..request.call(()) });
+        }
     };
+    let label = Signal::derive(move || button_label(&button_name, format.get()));
+    let theme = store_value(TableTheme::from_context());
+    view! {
+        <div class="flex justify-start gap-2 items-center">
+            <DownloadButton label=label on_click=start/>
+            <Show when=move || export.pending().get()>
+                <span class=theme.with_value(|theme| theme.spinner.clone())></span>
+                <span class="text-xs opacity-50 font-extralight">"Preparing File"</span>
+            </Show>
+            {move || error().map(|error| view! {
+                <span class=theme.with_value(|theme| format!("text-xs/3 opacity-50 font-extralight {}", theme.error_text))>{format!("Error Preparing Download File: {}", error)}</span>
+            })}
+        </div>
+    }
+}
+
+/// Select of an export option (format or scope), hidden when there is only one option
+#[allow(non_snake_case)]
+#[component]
+fn ExportPicker<T: Copy + PartialEq + 'static>(options: Vec<T>, label: fn(&T) -> &'static str, selected: RwSignal<T>) -> impl IntoView {
+    let options = store_value(options);
+    view! {
+        <Show when=move || options.with_value(|options| options.len() > 1)>
+            <select
+                class=TableTheme::from_context().select
+                on:change=move |event| {
+                    let value = event_target_value(&event);
+                    if let Some(option) = options.with_value(|options| options.iter().copied().find(|option| label(option) == value)) {
+                        selected.set(option);
+                    }
+                }
+            >
+                {options
+                    .get_value()
+                    .into_iter()
+                    .map(|option| view! {
+                        <option value=label(&option) selected=move || selected.get() == option>{label(&option)}</option>
+                    })
+                    .collect_view()}
+            </select>
+        </Show>
+    }
+}
+
+#[allow(non_snake_case)]
+#[component]
+fn DownloadButton(label: Signal<String>, on_click: impl Fn() + 'static) -> impl IntoView {
     view! {
         <button
-            class="font-normal btn btn-xs btn-ghost bg-base-100 rounded text-xs"
-            on:click=move |_| download()
+            class=TableTheme::from_context().download_button
+            on:click=move |_| on_click()
         >
             <div class="flex gap-2 justify-normal text-center items-center content-center">
                 <span>
@@ -629,13 +1389,19 @@ pub fn DownloadCsvAnchor(
                         ></path>
                     </svg>
                 </span>
-                <span class="font-extralight">{button_placeholder()}</span>
+                <span class="font-extralight">{label}</span>
             </div>
         </button>
     }
 }
 
-
+/// The label of a download button, the label of the format when none is given
+fn button_label(button_name: &str, format: ExportFormat) -> String {
+    match button_name.is_empty() {
+        true => format.label().to_string(),
+        false => button_name.to_string(),
+    }
+}
 
 /// Fix maximum button number to 5
 fn generate_button_numbers(current_page: u32, total_page: u32) -> Vec<u32> {
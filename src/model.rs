@@ -1,6 +1,18 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::rc::Rc;
+
+use leptos::{untrack, Callback, View};
 use serde_json::Value;
 use serde::{Deserialize, Serialize};
 
+use crate::export::{ExportFormat, ExportScope};
+use crate::format::{parse_datetime, parse_datetime_value, CurrencyFormat, DateFormat, DateZone, Locale, NumberFormat};
+use crate::selection::RowSelection;
+use crate::state::TableState;
+use crate::style::{rule_classes, StyleCondition, StyleRule};
+use crate::theme::TableTheme;
+
 /// A struct representing a table header with extra data
 /// 
 /// # Arguments
@@ -10,12 +22,23 @@ use serde::{Deserialize, Serialize};
 /// * `display_name` - The name of the column to display in the table
 /// * `is_currency` - A boolean to check if the column is a currency (if true you can append a certain column of currency)
 /// * `currency` - The name of the column of a currency
-/// * `is_number_styled` - A boolean to check if the column is a number (if true style is "text-success" else "text-error")
+/// * `is_number_styled` - A boolean to check if the column is a number (if true style is the `success_text` of the theme else the `error_text`)
 /// * `default_value` - use this if the data is not parseable
-/// * `style_when_success` - Add "text-success" to this column if the value is equals to this value
-/// * `style_when_error` - Add "text-error" to this column if the value is equals to this value
+/// * `style_when_success` - Add the `success_text` of the theme to this column if the value is equals to this value
+/// * `style_when_error` - Add the `error_text` of the theme to this column if the value is equals to this value
 /// * `to_uppercase` - A boolean to check if the column is a string (if true the value will be converted to uppercase)
 /// * `prefix` - Optional string to be added before the value
+/// * `filter` - Optional filter of the column, rendered in the filter row under the header
+/// * `kind` - The kind of the column, drives the formatting, alignment, sorting and export of the values (default `Text`)
+/// * `renderer` - Optional renderer of the cells of the column, the value is displayed as text when none is given (not serialized)
+/// * `array_display` - How an array value is displayed, joined with a separator (default) or as chips
+/// * `object_display` - How an object value is displayed, as a collapsible JSON preview (default) or by one of its keys
+/// * `null_value` - Optional text displayed when the value is `null`, `default_value` is used when none is given (and when the field is missing)
+/// * `number_format` - Optional formatting of the numeric values (decimals, rounding, separators, negative style), `is_number_styled` still reads the raw value
+/// * `currency_format` - Optional formatting of the amounts with the code of the `currency` column (ISO 4217 decimals, symbol placement, crypto precision)
+/// * `date_format` - Optional parsing, zone and display of the timestamps of a `Date` or `DateTime` column
+/// * `priority` - The priority of the column on small screens with `MobileLayout::Priority`, 0 (default) is always visible and higher numbers are hidden first
+/// * `styles` - Ordered style rules of the cells, checked against the raw value after the rules of `is_number_styled`, `style_when_success` and `style_when_error`
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct TableHeader {
@@ -30,9 +53,32 @@ pub struct TableHeader {
     pub style_when_error: String,
     pub to_uppercase: bool,
     pub prefix: Option<String>,
+    #[serde(default)]
+    pub filter: Option<FilterKind>,
+    #[serde(default)]
+    pub kind: ColumnKind,
+    #[serde(skip)]
+    pub renderer: Option<CellRenderer>,
+    #[serde(default)]
+    pub array_display: ArrayDisplay,
+    #[serde(default)]
+    pub object_display: ObjectDisplay,
+    #[serde(default)]
+    pub null_value: Option<String>,
+    #[serde(default)]
+    pub number_format: Option<NumberFormat>,
+    #[serde(default)]
+    pub currency_format: Option<CurrencyFormat>,
+    #[serde(default)]
+    pub date_format: Option<DateFormat>,
+    #[serde(default)]
+    pub styles: Vec<StyleRule>,
+    #[serde(default)]
+    pub priority: u8,
 }
 
 impl TableHeader {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(name: &str, sort_name: &str, display_name: &str, is_currency: bool, currency: &str, is_number_styled: bool, default_value: &str, style_when_success: &str, style_when_error: &str, to_uppercase: bool, prefix: Option<String>) -> Self {
         Self {
             name: name.to_string(),
@@ -46,32 +92,1202 @@ impl TableHeader {
             style_when_error: style_when_error.to_string(),
             to_uppercase,
             prefix,
+            filter: None,
+            kind: ColumnKind::Text,
+            renderer: None,
+            array_display: ArrayDisplay::default(),
+            object_display: ObjectDisplay::default(),
+            null_value: None,
+            number_format: None,
+            currency_format: None,
+            date_format: None,
+            styles: Vec::new(),
+            priority: 0,
         }
     }
 
+    /// The value of the column as text, `name` can be a nested path (see `resolve_path`)
+    ///
+    /// Arrays are joined by the separator of `array_display`, objects are displayed by `object_display`
+    /// and `null` is displayed as `null_value`, anything else not displayable falls back to `default_value`
     pub fn find(&self, json_value: &Value) -> String {
-        match json_value.get(&self.name) {
-            Some(value) => {
-                match value {
-                    Value::String(s) => s.clone(),
-                    Value::Number(n) => n.to_string(),
-                    Value::Bool(b) => b.to_string(),
-                    _ => self.default_value.clone(),
+        match resolve_path(json_value, &self.name) {
+            Some(Value::Null) => self.null_value.clone().unwrap_or_else(|| self.default_value.clone()),
+            Some(value) => self.value_text(value).unwrap_or_else(|| self.default_value.clone()),
+            None => self.default_value.clone(),
+        }
+    }
+
+    /// The items of an array value as text, `None` when the value is not an array
+    pub fn find_items(&self, json_value: &Value) -> Option<Vec<String>> {
+        match resolve_path(json_value, &self.name) {
+            Some(Value::Array(items)) => Some(items.iter().filter_map(|item| self.value_text(item)).collect()),
+            _ => None,
+        }
+    }
+
+    /// Whether the value is an explicit `null` (and not a missing field)
+    pub fn is_null(&self, json_value: &Value) -> bool {
+        matches!(resolve_path(json_value, &self.name), Some(Value::Null))
+    }
+
+    fn value_text(&self, value: &Value) -> Option<String> {
+        match value {
+            Value::String(s) => Some(s.clone()),
+            Value::Number(n) => Some(n.to_string()),
+            Value::Bool(b) => Some(b.to_string()),
+            Value::Null => None,
+            Value::Array(items) => {
+                let items: Vec<String> = items.iter().filter_map(|item| self.value_text(item)).collect();
+                match (items.is_empty(), &self.array_display) {
+                    (true, _) => None,
+                    (false, ArrayDisplay::Joined(separator)) => Some(items.join(separator)),
+                    (false, ArrayDisplay::Chips) => Some(items.join(", ")),
                 }
+            }
+            Value::Object(_) => match &self.object_display {
+                ObjectDisplay::Key(key) => resolve_path(value, key).and_then(|v| self.value_text(v)),
+                ObjectDisplay::Json => Some(value.to_string()),
             },
-            None => self.default_value.clone(),
         }
     }
     pub fn find_currency(&self, json_value: &Value) -> String {
-        match json_value.get(&self.currency) {
-            Some(value) => {
-                match value {
-                    Value::String(s) => s.clone(),
-                    _ => String::from(""),
+        match resolve_path(json_value, &self.currency) {
+            Some(Value::String(s)) => s.clone(),
+            _ => String::from(""),
+        }
+    }
+
+    /// A text column, sorted by `name` and labeled `name` until set otherwise
+    ///
+    /// ```ignore
+    /// TableHeader::text("customer_name").label("Customer").sortable_as("customer.name").uppercase()
+    /// ```
+    pub fn text(name: &str) -> Self {
+        Self::of_kind(name, ColumnKind::Text)
+    }
+
+    pub fn integer(name: &str) -> Self {
+        Self::of_kind(name, ColumnKind::Integer)
+    }
+
+    pub fn decimal(name: &str, precision: usize) -> Self {
+        Self::of_kind(name, ColumnKind::Decimal { precision })
+    }
+
+    /// A currency column, use `currency_from` to append the currency column
+    pub fn currency(name: &str) -> Self {
+        Self::of_kind(name, ColumnKind::Currency)
+    }
+
+    pub fn percent(name: &str, precision: usize) -> Self {
+        Self::of_kind(name, ColumnKind::Percent { precision })
+    }
+
+    pub fn date(name: &str) -> Self {
+        Self::of_kind(name, ColumnKind::Date)
+    }
+
+    pub fn datetime(name: &str) -> Self {
+        Self::of_kind(name, ColumnKind::DateTime)
+    }
+
+    pub fn duration(name: &str) -> Self {
+        Self::of_kind(name, ColumnKind::Duration)
+    }
+
+    pub fn boolean(name: &str) -> Self {
+        Self::of_kind(name, ColumnKind::Boolean)
+    }
+
+    pub fn enumeration(name: &str, options: &[&str]) -> Self {
+        Self::of_kind(name, ColumnKind::Enum(options.iter().map(|o| o.to_string()).collect()))
+    }
+
+    pub fn of_kind(name: &str, kind: ColumnKind) -> Self {
+        Self {
+            name: name.to_string(),
+            sort_name: name.to_string(),
+            display_name: name.to_string(),
+            kind,
+            ..Default::default()
+        }
+    }
+
+    /// The name of the column to display in the table
+    pub fn label(mut self, display_name: &str) -> Self {
+        self.display_name = display_name.to_string();
+        self
+    }
+
+    /// The name of the column to sort and filter by (e.g. the column in database)
+    pub fn sortable_as(mut self, sort_name: &str) -> Self {
+        self.sort_name = sort_name.to_string();
+        self
+    }
+
+    /// Append the value of this column (e.g. `USD`) after the value
+    pub fn currency_from(mut self, currency: &str) -> Self {
+        self.is_currency = true;
+        self.currency = currency.to_string();
+        self
+    }
+
+    /// Style positive values as success and negative values as error
+    pub fn number_styled(mut self) -> Self {
+        self.is_number_styled = true;
+        self
+    }
+
+    /// The value displayed when the value is missing or not displayable
+    pub fn default_value(mut self, default_value: &str) -> Self {
+        self.default_value = default_value.to_string();
+        self
+    }
+
+    /// Style the value as success when it contains this text
+    pub fn success_when(mut self, text: &str) -> Self {
+        self.style_when_success = text.to_string();
+        self
+    }
+
+    /// Style the value as error when it contains this text
+    pub fn error_when(mut self, text: &str) -> Self {
+        self.style_when_error = text.to_string();
+        self
+    }
+
+    pub fn uppercase(mut self) -> Self {
+        self.to_uppercase = true;
+        self
+    }
+
+    /// Text displayed before the value (e.g. a currency symbol)
+    pub fn prefix(mut self, prefix: &str) -> Self {
+        self.prefix = Some(prefix.to_string());
+        self
+    }
+
+    pub fn filter(mut self, filter: FilterKind) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
+    pub fn kind(mut self, kind: ColumnKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    pub fn renderer(mut self, renderer: CellRenderer) -> Self {
+        self.renderer = Some(renderer);
+        self
+    }
+
+    /// Display array values as chips
+    pub fn chips(mut self) -> Self {
+        self.array_display = ArrayDisplay::Chips;
+        self
+    }
+
+    /// Display array values joined with this separator
+    pub fn joined(mut self, separator: &str) -> Self {
+        self.array_display = ArrayDisplay::Joined(separator.to_string());
+        self
+    }
+
+    /// Display object values (and objects in arrays) by this key, which can be a nested path
+    pub fn object_key(mut self, key: &str) -> Self {
+        self.object_display = ObjectDisplay::Key(key.to_string());
+        self
+    }
+
+    /// The value displayed when the value is `null`, distinct from `default_value` of a missing field
+    pub fn null_as(mut self, null_value: &str) -> Self {
+        self.null_value = Some(null_value.to_string());
+        self
+    }
+
+    /// Format the numeric values, see `NumberFormat`
+    pub fn number_format(mut self, number_format: NumberFormat) -> Self {
+        self.number_format = Some(number_format);
+        self
+    }
+
+    /// Format the amounts with the code of the `currency` column, see `CurrencyFormat`
+    pub fn currency_format(mut self, currency_format: CurrencyFormat) -> Self {
+        self.currency_format = Some(currency_format);
+        self
+    }
+
+    /// Parse, convert and display the timestamps with a `DateFormat`
+    pub fn date_format(mut self, date_format: DateFormat) -> Self {
+        self.date_format = Some(date_format);
+        self
+    }
+
+    /// The priority of the column on small screens, see `MobileLayout::Priority`
+    pub fn priority(mut self, priority: u8) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Add a style rule, see `StyleRule`
+    pub fn style(mut self, rule: StyleRule) -> Self {
+        self.styles.push(rule);
+        self
+    }
+
+    /// The style rules of the cells, the rules of `is_number_styled`, `style_when_success` and `style_when_error` come first
+    /// with the `success_text` and `error_text` classes of the theme
+    pub fn style_rules(&self, theme: &TableTheme) -> Vec<StyleRule> {
+        let mut rules = Vec::new();
+        if self.is_number_styled {
+            rules.push(StyleRule::new(StyleCondition::AtLeast(0.0), &theme.success_text));
+            rules.push(StyleRule::new(StyleCondition::Below(0.0), &theme.error_text));
+        }
+        if !self.style_when_success.is_empty() {
+            rules.push(StyleRule::new(StyleCondition::Contains(self.style_when_success.clone()), &theme.success_text));
+        }
+        if !self.style_when_error.is_empty() {
+            rules.push(StyleRule::new(StyleCondition::Contains(self.style_when_error.clone()), &theme.error_text));
+        }
+        rules.extend(self.styles.iter().cloned());
+        rules
+    }
+
+    /// The classes of the cell of a row, from the style rules checked against the raw value
+    pub fn cell_class(&self, json_value: &Value, theme: &TableTheme) -> String {
+        rule_classes(&self.style_rules(theme), Some(&self.find(json_value)), json_value)
+    }
+
+    /// Render the cells with a view built from the row, see `CellRenderer::custom`
+    pub fn render_with(self, render: impl Fn(&Value) -> View + 'static) -> Self {
+        self.renderer(CellRenderer::custom(render))
+    }
+
+    /// Check the header for misconfigurations, every problem found is reported
+    pub fn validate(&self) -> Result<(), Vec<HeaderConfigError>> {
+        let mut errors = Vec::new();
+        if self.name.trim().is_empty() {
+            errors.push(HeaderConfigError::EmptyName);
+        }
+        if self.sort_name.trim().is_empty() {
+            errors.push(HeaderConfigError::EmptySortName(self.name.clone()));
+        }
+        if self.has_currency() && self.currency.trim().is_empty() {
+            errors.push(HeaderConfigError::MissingCurrencyField(self.name.clone()));
+        }
+        if matches!(&self.kind, ColumnKind::Enum(options) if options.is_empty()) {
+            errors.push(HeaderConfigError::EmptyEnumOptions(self.name.clone()));
+        }
+        if matches!(&self.filter, Some(FilterKind::Select(options)) if options.is_empty()) {
+            errors.push(HeaderConfigError::EmptySelectOptions(self.name.clone()));
+        }
+        if !self.style_when_success.is_empty() && self.style_when_success.eq_ignore_ascii_case(&self.style_when_error) {
+            errors.push(HeaderConfigError::ConflictingStyles(self.name.clone()));
+        }
+        match errors.is_empty() {
+            true => Ok(()),
+            false => Err(errors),
+        }
+    }
+
+    /// The currency column is appended to the value
+    pub fn has_currency(&self) -> bool {
+        self.is_currency || self.kind == ColumnKind::Currency
+    }
+
+    /// The value formatted by the kind of the column, as displayed in the table
+    pub fn display_value(&self, json_value: &Value) -> String {
+        if let Some(amount) = self.format_currency(json_value) {
+            return amount;
+        }
+        if let Some(date) = self.format_date(json_value) {
+            return date;
+        }
+        let raw = self.find(json_value);
+        self.number_format
+            .as_ref()
+            .and_then(|number_format| self.kind.format_number(&raw, number_format))
+            .or_else(|| self.kind.format(&raw))
+            .unwrap_or(raw)
+    }
+
+    /// The value as exported to a file, numbers without formatting and dates as ISO 8601
+    pub fn export_value(&self, json_value: &Value) -> String {
+        let in_exports = self.number_format.as_ref().is_some_and(|number_format| number_format.in_exports);
+        let raw = self.find(json_value);
+        match (&self.currency_format, raw.trim().parse::<f64>(), in_exports) {
+            (Some(_), Ok(_), true) => return self.format_currency(json_value).unwrap_or(raw),
+            (Some(currency_format), Ok(amount), false) => {
+                let decimals = currency_format.decimals_of(&self.find_currency(json_value));
+                return NumberFormat::default().thousands_separator("").format(amount, Some(decimals));
+            }
+            _ => {}
+        }
+        if let Some(date) = self.parse_date(json_value) {
+            return match self.kind {
+                ColumnKind::Date => date.format("%Y-%m-%d").to_string(),
+                _ => date.to_rfc3339(),
+            };
+        }
+        self.number_format
+            .as_ref()
+            .filter(|number_format| number_format.in_exports)
+            .and_then(|number_format| self.kind.format_number(&raw, number_format))
+            .or_else(|| self.kind.export(&raw))
+            .unwrap_or(raw)
+    }
+
+    /// The local date and time of a `Date` or `DateTime` column, converted to the zone of the `date_format` when one is set
+    pub fn find_datetime(&self, json_value: &Value) -> Option<chrono::NaiveDateTime> {
+        if !matches!(self.kind, ColumnKind::Date | ColumnKind::DateTime) {
+            return None;
+        }
+        let date = self.parse_date(json_value)?;
+        match &self.date_format {
+            Some(date_format) => Some(date_format.convert(date).naive_local()),
+            None => Some(date.naive_local()),
+        }
+    }
+
+    /// Whether the date of the value is between `from` and `to` (inclusive, `YYYY-MM-DD`), in the zone of the `date_format` when one is set,
+    /// `None` when the value or a bound is not a date
+    pub fn date_in_range(&self, json_value: &Value, from: Option<&str>, to: Option<&str>) -> Option<bool> {
+        let date = self.parse_date(json_value)?;
+        let date = match &self.date_format {
+            Some(date_format) => date_format.convert(date).date_naive(),
+            None => date.date_naive(),
+        };
+        let parse = |bound: Option<&str>| bound.map(|bound| chrono::NaiveDate::parse_from_str(bound, "%Y-%m-%d")).transpose().ok();
+        let (from, to) = (parse(from)?, parse(to)?);
+        Some(from.is_none_or(|from| date >= from) && to.is_none_or(|to| date <= to))
+    }
+
+    /// The timestamp of the value with the `date_format`, or of a `Date` or `DateTime` column without one,
+    /// JSON numbers are Unix timestamps
+    fn parse_date(&self, json_value: &Value) -> Option<chrono::DateTime<chrono::FixedOffset>> {
+        let value = resolve_path(json_value, &self.name)?;
+        match (&self.date_format, &self.kind) {
+            (Some(date_format), _) => date_format.parse_value(value),
+            (None, ColumnKind::Date | ColumnKind::DateTime) => parse_datetime_value(value),
+            (None, _) => None,
+        }
+    }
+
+    /// The amount formatted with its currency, `None` without a `currency_format` or when the value is not a number
+    pub fn format_currency(&self, json_value: &Value) -> Option<String> {
+        let currency_format = self.currency_format.as_ref()?;
+        let amount = self.find(json_value).trim().parse::<f64>().ok()?;
+        let code = match self.currency.is_empty() {
+            true => String::new(),
+            false => self.find_currency(json_value),
+        };
+        Some(currency_format.format(amount, &code, self.number_format.as_ref()))
+    }
+
+    /// The timestamp displayed by the `date_format` (as parsed without one), `None` when the column is not a date or the value is not a timestamp
+    fn format_date(&self, json_value: &Value) -> Option<String> {
+        let pattern = match self.kind {
+            ColumnKind::Date => "%Y-%m-%d",
+            ColumnKind::DateTime => "%Y-%m-%d %H:%M:%S",
+            _ => return None,
+        };
+        let date = self.parse_date(json_value)?;
+        Some(match &self.date_format {
+            Some(date_format) => date_format.display(date, pattern),
+            None => date.format(pattern).to_string(),
+        })
+    }
+
+    /// Compare the values of two rows by the kind of the column, timestamps are compared chronologically
+    pub fn compare(&self, a: &Value, b: &Value) -> Ordering {
+        match self.kind {
+            ColumnKind::Date | ColumnKind::DateTime => {
+                let key = |row: &Value| self.parse_date(row).map(|d| d.timestamp_millis() as f64);
+                compare_keys(key(a), key(b)).then_with(|| compare_text(&self.find(a), &self.find(b)))
+            }
+            _ => self.kind.compare(&self.find(a), &self.find(b)),
+        }
+    }
+}
+
+/// How an array value is displayed in a cell
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ArrayDisplay {
+    /// The items as text joined with the separator
+    Joined(String),
+    /// Every item in a badge
+    Chips,
+}
+
+impl Default for ArrayDisplay {
+    fn default() -> Self {
+        ArrayDisplay::Joined(", ".to_string())
+    }
+}
+
+/// How an object value is displayed in a cell
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ObjectDisplay {
+    /// A one line preview of the JSON that expands to the formatted JSON
+    #[default]
+    Json,
+    /// The value of a key (or nested path) of the object
+    Key(String),
+}
+
+/// A misconfiguration of a `TableHeader`, reported by `TableHeader::validate`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HeaderConfigError {
+    EmptyName,
+    EmptySortName(String),
+    MissingCurrencyField(String),
+    EmptyEnumOptions(String),
+    EmptySelectOptions(String),
+    ConflictingStyles(String),
+}
+
+impl fmt::Display for HeaderConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HeaderConfigError::EmptyName => write!(f, "header has an empty name"),
+            HeaderConfigError::EmptySortName(name) => write!(f, "header `{}` has an empty sort name", name),
+            HeaderConfigError::MissingCurrencyField(name) => write!(f, "header `{}` is a currency with an empty currency field", name),
+            HeaderConfigError::EmptyEnumOptions(name) => write!(f, "header `{}` is an enum without options", name),
+            HeaderConfigError::EmptySelectOptions(name) => write!(f, "header `{}` has a select filter without options", name),
+            HeaderConfigError::ConflictingStyles(name) => write!(f, "header `{}` styles the same value as success and error", name),
+        }
+    }
+}
+
+impl std::error::Error for HeaderConfigError {}
+
+/// The kind of a column, drives the formatting, alignment, sorting and export of its values
+///
+/// * `Text` - As is (default)
+/// * `Integer` - Rounded number
+/// * `Decimal` - Number with a fixed number of decimals
+/// * `Currency` - Number with 2 decimals, the currency column of the header is appended
+/// * `Percent` - Ratio displayed as a percentage with a fixed number of decimals (`0.125` is `12.50%`)
+/// * `Date` - ISO 8601 date or timestamp displayed as `YYYY-MM-DD`
+/// * `DateTime` - ISO 8601 timestamp displayed as `YYYY-MM-DD HH:MM:SS`
+/// * `Duration` - Number of seconds displayed as `1h 2m 3s`
+/// * `Boolean` - Displayed as `Yes` or `No`
+/// * `Enum` - One of the options, sorted in the order of the options
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+pub enum ColumnKind {
+    #[default]
+    Text,
+    Integer,
+    Decimal { precision: usize },
+    Currency,
+    Percent { precision: usize },
+    Date,
+    DateTime,
+    Duration,
+    Boolean,
+    Enum(Vec<String>),
+}
+
+impl ColumnKind {
+    /// Numbers are right aligned
+    pub fn is_numeric(&self) -> bool {
+        matches!(
+            self,
+            ColumnKind::Integer | ColumnKind::Decimal { .. } | ColumnKind::Currency | ColumnKind::Percent { .. } | ColumnKind::Duration
+        )
+    }
+
+    /// Format a raw value, `None` when the value does not fit the kind
+    pub fn format(&self, raw: &str) -> Option<String> {
+        let number = || raw.trim().parse::<f64>().ok();
+        match self {
+            ColumnKind::Text | ColumnKind::Enum(_) => None,
+            ColumnKind::Integer => number().map(|n| format!("{:.0}", n)),
+            ColumnKind::Decimal { precision } => number().map(|n| format!("{:.*}", precision, n)),
+            ColumnKind::Currency => number().map(|n| format!("{:.2}", n)),
+            ColumnKind::Percent { precision } => number().map(|n| format!("{:.*}%", precision, n * 100.0)),
+            ColumnKind::Date => parse_datetime(raw).map(|d| d.format("%Y-%m-%d").to_string()),
+            ColumnKind::DateTime => parse_datetime(raw).map(|d| d.format("%Y-%m-%d %H:%M:%S").to_string()),
+            ColumnKind::Duration => number().map(format_duration),
+            ColumnKind::Boolean => parse_bool(raw).map(|b| match b {
+                true => "Yes".to_string(),
+                false => "No".to_string(),
+            }),
+        }
+    }
+
+    /// Format a raw number with a `NumberFormat`, `None` when the value is not a number or the kind is not formatted as a number
+    pub fn format_number(&self, raw: &str, number_format: &NumberFormat) -> Option<String> {
+        let number = raw.trim().parse::<f64>().ok()?;
+        match self {
+            ColumnKind::Text => Some(number_format.format(number, None)),
+            ColumnKind::Integer => Some(number_format.format(number, Some(0))),
+            ColumnKind::Decimal { precision } => Some(number_format.format(number, Some(*precision))),
+            ColumnKind::Currency => Some(number_format.format(number, Some(2))),
+            ColumnKind::Percent { precision } => Some(format!("{}%", number_format.format(number * 100.0, Some(*precision)))),
+            _ => None,
+        }
+    }
+
+    /// The raw value for an export, numbers are not rounded, `None` when the value does not fit the kind
+    pub fn export(&self, raw: &str) -> Option<String> {
+        let number = || raw.trim().parse::<f64>().ok();
+        match self {
+            ColumnKind::Text | ColumnKind::Enum(_) | ColumnKind::Duration => None,
+            ColumnKind::Integer | ColumnKind::Decimal { .. } | ColumnKind::Currency | ColumnKind::Percent { .. } => number().map(|n| n.to_string()),
+            ColumnKind::Date => parse_datetime(raw).map(|d| d.format("%Y-%m-%d").to_string()),
+            ColumnKind::DateTime => parse_datetime(raw).map(|d| d.to_rfc3339()),
+            ColumnKind::Boolean => parse_bool(raw).map(|b| b.to_string()),
+        }
+    }
+
+    /// Compare two raw values, values that do not fit the kind come last and ties are compared as text
+    ///
+    /// In a `Text` column the numbers come first, in order, then the text.
+    pub fn compare(&self, a: &str, b: &str) -> Ordering {
+        let number = |s: &str| s.trim().parse::<f64>().ok();
+        let key = |s: &str| match self {
+            ColumnKind::Date | ColumnKind::DateTime => parse_datetime(s).map(|d| d.timestamp_millis() as f64),
+            ColumnKind::Boolean => parse_bool(s).map(|b| f64::from(u8::from(b))),
+            ColumnKind::Enum(options) => options.iter().position(|o| o.eq_ignore_ascii_case(s)).map(|i| i as f64),
+            _ => number(s),
+        };
+        compare_keys(key(a), key(b)).then_with(|| compare_text(a, b))
+    }
+}
+
+/// Compare two sort keys, the values without a key come after the others
+///
+/// Every value is ranked by whether it has a key, so the order stays total when keyed and unkeyed values are mixed.
+fn compare_keys(a: Option<f64>, b: Option<f64>) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => a.total_cmp(&b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+/// Compare two texts ignoring the case, then as is
+fn compare_text(a: &str, b: &str) -> Ordering {
+    a.to_uppercase().cmp(&b.to_uppercase()).then_with(|| a.cmp(b))
+}
+
+fn parse_bool(raw: &str) -> Option<bool> {
+    match raw.trim().to_lowercase().as_str() {
+        "true" | "1" | "yes" => Some(true),
+        "false" | "0" | "no" => Some(false),
+        _ => None,
+    }
+}
+
+/// Format a number of seconds as `1d 2h 3m 4s`, skipping the empty units
+fn format_duration(seconds: f64) -> String {
+    let sign = if seconds < 0.0 { "-" } else { "" };
+    let mut rest = seconds.abs().round() as u64;
+    let mut parts = Vec::new();
+    for (unit, size) in [("d", 86_400), ("h", 3_600), ("m", 60)] {
+        if rest >= size {
+            parts.push(format!("{}{}", rest / size, unit));
+            rest %= size;
+        }
+    }
+    if rest > 0 || parts.is_empty() {
+        parts.push(format!("{}s", rest));
+    }
+    format!("{}{}", sign, parts.join(" "))
+}
+
+/// Where the sorting, searching and pagination of the table happens
+///
+/// * `Server` - The data is already sorted, searched and sliced by the backend (default)
+/// * `Client` - The data holds every row, the table sorts, searches and slices it in memory
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TableMode {
+    #[default]
+    Server,
+    Client,
+}
+
+/// The renderer of the cells of a column
+///
+/// * `Link` - The value as a link, to the URL in `href_field` (or the value itself when `None`).
+///   Only `http`, `https`, `mailto` and relative URLs are linked, any other URL renders the value as text
+/// * `Badge` - The value in a badge with extra classes
+/// * `Image` - The value as the source of an image with classes
+/// * `Button` - A button with a label (or the value), called with the row when clicked
+/// * `Custom` - Any view built from the row, see `CellRenderer::custom`
+#[derive(Clone)]
+pub enum CellRenderer {
+    Link { href_field: Option<String>, new_tab: bool },
+    Badge { class: String },
+    Image { class: String },
+    Button { label: Option<String>, on_click: Callback<Value> },
+    Custom(Rc<dyn Fn(&Value) -> View>),
+}
+
+impl CellRenderer {
+    /// A renderer built from the row
+    ///
+    /// ```ignore
+    /// CellRenderer::custom(|row| view! { <a href=format!("/users/{}", row["user_id"])>{row["name"].to_string()}</a> }.into_view())
+    /// ```
+    pub fn custom(render: impl Fn(&Value) -> View + 'static) -> Self {
+        CellRenderer::Custom(Rc::new(render))
+    }
+}
+
+/// The URL is relative or uses the `http`, `https` or `mailto` scheme
+pub fn is_safe_href(href: &str) -> bool {
+    // Browsers drop tabs and newlines anywhere in a URL and leading control characters and spaces
+    let href = href.chars().filter(|c| !matches!(c, '\t' | '\n' | '\r')).collect::<String>();
+    let href = href.trim_start_matches(|c: char| c.is_ascii_control() || c == ' ');
+    match href.find([':', '/', '?', '#']) {
+        Some(end) if href[end..].starts_with(':') => {
+            let scheme = href[..end].to_ascii_lowercase();
+            matches!(scheme.as_str(), "http" | "https" | "mailto")
+        }
+        _ => true,
+    }
+}
+
+impl fmt::Debug for CellRenderer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CellRenderer::Link { href_field, new_tab } => f.debug_struct("Link").field("href_field", href_field).field("new_tab", new_tab).finish(),
+            CellRenderer::Badge { class } => f.debug_struct("Badge").field("class", class).finish(),
+            CellRenderer::Image { class } => f.debug_struct("Image").field("class", class).finish(),
+            CellRenderer::Button { label, .. } => f.debug_struct("Button").field("label", label).finish_non_exhaustive(),
+            CellRenderer::Custom(_) => f.write_str("Custom(..)"),
+        }
+    }
+}
+
+/// The kind of filter of a column, rendered in the filter row under the header
+///
+/// * `Contains` - Text input, the value contains the text (case insensitive)
+/// * `Equals` - Text input, the value is equal to the text
+/// * `NumberRange` - Two number inputs, the value is between the minimum and the maximum
+/// * `DateRange` - Two date inputs, the value is between the two dates (inclusive), parsed with the `DateFormat` of the header in client mode
+///   and compared as ISO 8601 strings by the expression sent to the server
+/// * `Select` - Select from the options, the value is equal to the selected option
+/// * `Boolean` - Select true or false
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub enum FilterKind {
+    Contains,
+    Equals,
+    NumberRange,
+    DateRange,
+    Select(Vec<String>),
+    Boolean,
+}
+
+/// The value of a column filter
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "op", content = "value", rename_all = "snake_case")]
+pub enum ColumnFilterValue {
+    Contains(String),
+    Equals(String),
+    NumberRange { min: Option<f64>, max: Option<f64> },
+    /// Dates as `YYYY-MM-DD`
+    DateRange { from: Option<String>, to: Option<String> },
+    Boolean(bool),
+}
+
+/// A struct representing the filter of a single column
+///
+/// # Arguments
+///
+/// * `field` - The `sort_name` of the header being filtered (can be the name of the column in database)
+/// * `value` - The value of the filter
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ColumnFilter {
+    pub field: String,
+    pub value: ColumnFilterValue,
+}
+
+impl ColumnFilter {
+    /// The filter as an expression on `field`
+    pub fn to_expr(&self) -> FilterExpr {
+        let field = self.field.as_str();
+        match &self.value {
+            ColumnFilterValue::Contains(text) => FilterExpr::contains(field, text),
+            ColumnFilterValue::Equals(text) => FilterExpr::equals(field, text.as_str()),
+            ColumnFilterValue::NumberRange { min, max } => FilterExpr::at_least_at_most(field, min.map(Value::from), max.map(Value::from)),
+            ColumnFilterValue::DateRange { from, to } => {
+                // Timestamps of the last day are before the next day, so `to` includes the whole day
+                let from = from.clone().map(|from| FilterExpr::at_least(field, from));
+                let to = to.as_ref().map(|to| match chrono::NaiveDate::parse_from_str(to, "%Y-%m-%d").ok().and_then(|date| date.succ_opt()) {
+                    Some(next) => FilterExpr::less_than(field, next.format("%Y-%m-%d").to_string()),
+                    None => FilterExpr::at_most(field, to.as_str()),
+                });
+                match (from, to) {
+                    (Some(from), Some(to)) => FilterExpr::all(vec![from, to]),
+                    (from, to) => from.or(to).unwrap_or(FilterExpr::And(Vec::new())),
                 }
+            }
+            ColumnFilterValue::Boolean(b) => FilterExpr::equals(field, *b),
+        }
+    }
+}
+
+/// A typed filter expression, evaluated against rows and encoded for backends with a `FilterEncoder`
+///
+/// `field` is the key of the value in the row.
+/// Values are compared as numbers when both sides are numbers (or numeric strings), else as strings.
+///
+/// # Example
+///
+/// ```ignore
+/// let expr = FilterExpr::all(vec![
+///     FilterExpr::equals("current_status", "Gain"),
+///     FilterExpr::greater_than("profit_amount", 100),
+/// ]);
+/// let query = DirectusEncoder.encode(&expr)?; // filter[_and][0][current_status][_eq]=Gain&...
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum FilterExpr {
+    And(Vec<FilterExpr>),
+    Or(Vec<FilterExpr>),
+    Not(Box<FilterExpr>),
+    Eq { field: String, value: Value },
+    Ne { field: String, value: Value },
+    Lt { field: String, value: Value },
+    Gt { field: String, value: Value },
+    Gte { field: String, value: Value },
+    Lte { field: String, value: Value },
+    Between { field: String, from: Value, to: Value },
+    In { field: String, values: Vec<Value> },
+    Contains { field: String, value: String },
+    IsNull { field: String },
+}
+
+impl FilterExpr {
+    pub fn all(exprs: Vec<FilterExpr>) -> Self {
+        FilterExpr::And(exprs)
+    }
+
+    pub fn any(exprs: Vec<FilterExpr>) -> Self {
+        FilterExpr::Or(exprs)
+    }
+
+    pub fn negate(self) -> Self {
+        FilterExpr::Not(Box::new(self))
+    }
+
+    pub fn equals(field: &str, value: impl Into<Value>) -> Self {
+        FilterExpr::Eq { field: field.to_string(), value: value.into() }
+    }
+
+    pub fn not_equals(field: &str, value: impl Into<Value>) -> Self {
+        FilterExpr::Ne { field: field.to_string(), value: value.into() }
+    }
+
+    pub fn less_than(field: &str, value: impl Into<Value>) -> Self {
+        FilterExpr::Lt { field: field.to_string(), value: value.into() }
+    }
+
+    pub fn greater_than(field: &str, value: impl Into<Value>) -> Self {
+        FilterExpr::Gt { field: field.to_string(), value: value.into() }
+    }
+
+    pub fn at_least(field: &str, value: impl Into<Value>) -> Self {
+        FilterExpr::Gte { field: field.to_string(), value: value.into() }
+    }
+
+    pub fn at_most(field: &str, value: impl Into<Value>) -> Self {
+        FilterExpr::Lte { field: field.to_string(), value: value.into() }
+    }
+
+    /// Between `from` and `to`, inclusive
+    pub fn between(field: &str, from: impl Into<Value>, to: impl Into<Value>) -> Self {
+        FilterExpr::Between { field: field.to_string(), from: from.into(), to: to.into() }
+    }
+
+    pub fn is_in(field: &str, values: Vec<Value>) -> Self {
+        FilterExpr::In { field: field.to_string(), values }
+    }
+
+    /// Contains the text (case insensitive), or contains the value for arrays
+    pub fn contains(field: &str, value: &str) -> Self {
+        FilterExpr::Contains { field: field.to_string(), value: value.to_string() }
+    }
+
+    /// The field is missing or null
+    pub fn is_null(field: &str) -> Self {
+        FilterExpr::IsNull { field: field.to_string() }
+    }
+
+    /// An inclusive range where either bound is optional, `None` when both are missing
+    fn at_least_at_most(field: &str, min: Option<Value>, max: Option<Value>) -> Self {
+        match (min, max) {
+            (Some(min), Some(max)) => FilterExpr::between(field, min, max),
+            (Some(min), None) => FilterExpr::at_least(field, min),
+            (None, Some(max)) => FilterExpr::at_most(field, max),
+            (None, None) => FilterExpr::And(Vec::new()),
+        }
+    }
+
+    /// Evaluate the expression against a row
+    pub fn matches(&self, row: &Value) -> bool {
+        let field_value = |field: &str| resolve_path(row, field).unwrap_or(&Value::Null);
+        match self {
+            FilterExpr::And(exprs) => exprs.iter().all(|e| e.matches(row)),
+            FilterExpr::Or(exprs) => exprs.iter().any(|e| e.matches(row)),
+            FilterExpr::Not(expr) => !expr.matches(row),
+            FilterExpr::Eq { field, value } => compare_json(field_value(field), value) == Some(Ordering::Equal),
+            FilterExpr::Ne { field, value } => compare_json(field_value(field), value) != Some(Ordering::Equal),
+            FilterExpr::Lt { field, value } => compare_json(field_value(field), value) == Some(Ordering::Less),
+            FilterExpr::Gt { field, value } => compare_json(field_value(field), value) == Some(Ordering::Greater),
+            FilterExpr::Gte { field, value } => matches!(compare_json(field_value(field), value), Some(Ordering::Greater | Ordering::Equal)),
+            FilterExpr::Lte { field, value } => matches!(compare_json(field_value(field), value), Some(Ordering::Less | Ordering::Equal)),
+            FilterExpr::Between { field, from, to } => {
+                let value = field_value(field);
+                matches!(compare_json(value, from), Some(Ordering::Greater | Ordering::Equal))
+                    && matches!(compare_json(value, to), Some(Ordering::Less | Ordering::Equal))
+            }
+            FilterExpr::In { field, values } => {
+                let value = field_value(field);
+                values.iter().any(|v| compare_json(value, v) == Some(Ordering::Equal))
+            }
+            FilterExpr::Contains { field, value } => match field_value(field) {
+                Value::Array(items) => items.iter().any(|item| compare_json(item, &Value::from(value.as_str())) == Some(Ordering::Equal)),
+                Value::Null => false,
+                other => json_to_text(other).to_uppercase().contains(&value.to_uppercase()),
             },
-            None => String::from(""),
+            FilterExpr::IsNull { field } => field_value(field).is_null(),
+        }
+    }
+}
+
+/// Resolve a field in a row
+///
+/// # Arguments
+/// * `path` - A key (`name`), a dotted path with array indices (`user.profile.name`, `items[0].price`, `items.0.price`)
+///   or a RFC 6901 JSON pointer (`/user/profile/name`). A key present as is in the row takes precedence over a path
+pub fn resolve_path<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    if path.starts_with('/') {
+        return value.pointer(path);
+    }
+    if let Some(found) = value.get(path) {
+        return Some(found);
+    }
+    if path.is_empty() {
+        return None;
+    }
+    let mut current = value;
+    for segment in path.split('.') {
+        let (key, indices) = match segment.find('[') {
+            Some(start) => segment.split_at(start),
+            None => (segment, ""),
+        };
+        if !key.is_empty() {
+            current = match current {
+                Value::Array(items) => items.get(key.parse::<usize>().ok()?)?,
+                _ => current.get(key)?,
+            };
+        }
+        for index in indices.split_terminator(']') {
+            let index = index.strip_prefix('[')?.trim().parse::<usize>().ok()?;
+            current = current.get(index)?;
+        }
+    }
+    Some(current)
+}
+
+/// Compare two JSON values, numerically when both are numbers (or numeric strings), `None` when not comparable
+pub(crate) fn compare_json(a: &Value, b: &Value) -> Option<Ordering> {
+    let as_number = |v: &Value| match v {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse::<f64>().ok(),
+        _ => None,
+    };
+    match (a, b) {
+        (Value::Null, Value::Null) => Some(Ordering::Equal),
+        (Value::Null, _) | (_, Value::Null) => None,
+        (Value::Bool(a), Value::Bool(b)) => Some(a.cmp(b)),
+        (Value::String(a), Value::String(b)) => match (a.trim().parse::<f64>(), b.trim().parse::<f64>()) {
+            (Ok(a), Ok(b)) => a.partial_cmp(&b),
+            _ => Some(a.cmp(b)),
+        },
+        _ => match (as_number(a), as_number(b)) {
+            (Some(a), Some(b)) => a.partial_cmp(&b),
+            _ => Some(json_to_text(a).cmp(&json_to_text(b))),
+        },
+    }
+}
+
+/// The text of a value, strings without quotes
+pub(crate) fn json_to_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Error when an expression can not be expressed in the dialect of a `FilterEncoder`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FilterEncodeError(pub String);
+
+impl fmt::Display for FilterEncodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unsupported filter: {}", self.0)
+    }
+}
+
+impl std::error::Error for FilterEncodeError {}
+
+/// Encode a `FilterExpr` to the query string of a backend
+pub trait FilterEncoder {
+    fn encode(&self, expr: &FilterExpr) -> Result<String, FilterEncodeError>;
+}
+
+/// Directus style filter, `filter[field][_eq]=value`, with `_and`/`_or` groups
+///
+/// `Not` is pushed down to the leaves and encoded with the negated operators (`_neq`, `_gte`, `_nin`, `_nnull`, ...)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DirectusEncoder;
+
+impl DirectusEncoder {
+    /// The filter as a Directus JSON filter object, e.g. `{"field": {"_eq": "value"}}`
+    pub fn to_value(&self, expr: &FilterExpr) -> Value {
+        directus_value(expr, false)
+    }
+}
+
+impl FilterEncoder for DirectusEncoder {
+    fn encode(&self, expr: &FilterExpr) -> Result<String, FilterEncodeError> {
+        let mut params = Vec::new();
+        flatten_params("filter".to_string(), &self.to_value(expr), &mut params);
+        Ok(params
+            .into_iter()
+            .map(|(key, value)| format!("{}={}", encode_query_key(&key), encode_query_component(&value)))
+            .collect::<Vec<_>>()
+            .join("&"))
+    }
+}
+
+fn directus_value(expr: &FilterExpr, negated: bool) -> Value {
+    let leaf = |field: &str, op: &str, value: Value| {
+        let mut inner = serde_json::Map::new();
+        inner.insert(op.to_string(), value);
+        let mut outer = serde_json::Map::new();
+        outer.insert(field.to_string(), Value::Object(inner));
+        Value::Object(outer)
+    };
+    let group = |op: &str, exprs: &[FilterExpr], negated: bool| {
+        let mut outer = serde_json::Map::new();
+        outer.insert(op.to_string(), Value::Array(exprs.iter().map(|e| directus_value(e, negated)).collect()));
+        Value::Object(outer)
+    };
+    let list = |values: &[Value]| values.iter().map(json_to_text).collect::<Vec<_>>().join(",");
+    match (expr, negated) {
+        (FilterExpr::And(exprs), false) | (FilterExpr::Or(exprs), true) => group("_and", exprs, negated),
+        (FilterExpr::Or(exprs), false) | (FilterExpr::And(exprs), true) => group("_or", exprs, negated),
+        (FilterExpr::Not(expr), negated) => directus_value(expr, !negated),
+        (FilterExpr::Eq { field, value }, false) | (FilterExpr::Ne { field, value }, true) => leaf(field, "_eq", value.clone()),
+        (FilterExpr::Ne { field, value }, false) | (FilterExpr::Eq { field, value }, true) => leaf(field, "_neq", value.clone()),
+        (FilterExpr::Lt { field, value }, false) | (FilterExpr::Gte { field, value }, true) => leaf(field, "_lt", value.clone()),
+        (FilterExpr::Lt { field, value }, true) | (FilterExpr::Gte { field, value }, false) => leaf(field, "_gte", value.clone()),
+        (FilterExpr::Gt { field, value }, false) | (FilterExpr::Lte { field, value }, true) => leaf(field, "_gt", value.clone()),
+        (FilterExpr::Gt { field, value }, true) | (FilterExpr::Lte { field, value }, false) => leaf(field, "_lte", value.clone()),
+        (FilterExpr::Between { field, from, to }, false) => leaf(field, "_between", Value::from(list(&[from.clone(), to.clone()]))),
+        (FilterExpr::Between { field, from, to }, true) => leaf(field, "_nbetween", Value::from(list(&[from.clone(), to.clone()]))),
+        (FilterExpr::In { field, values }, false) => leaf(field, "_in", Value::from(list(values))),
+        (FilterExpr::In { field, values }, true) => leaf(field, "_nin", Value::from(list(values))),
+        (FilterExpr::Contains { field, value }, false) => leaf(field, "_icontains", Value::from(value.as_str())),
+        (FilterExpr::Contains { field, value }, true) => leaf(field, "_nicontains", Value::from(value.as_str())),
+        (FilterExpr::IsNull { field }, false) => leaf(field, "_null", Value::Bool(true)),
+        (FilterExpr::IsNull { field }, true) => leaf(field, "_nnull", Value::Bool(true)),
+    }
+}
+
+fn flatten_params(prefix: String, value: &Value, params: &mut Vec<(String, String)>) {
+    match value {
+        Value::Object(map) => map
+            .iter()
+            .for_each(|(key, value)| flatten_params(format!("{}[{}]", prefix, key), value, params)),
+        Value::Array(items) => items
+            .iter()
+            .enumerate()
+            .for_each(|(index, value)| flatten_params(format!("{}[{}]", prefix, index), value, params)),
+        other => params.push((prefix, json_to_text(other))),
+    }
+}
+
+/// Plain URL params joined by `&`, `field=value` for equality and `field{separator}op=value` for the rest
+/// (`ne`, `lt`, `gt`, `gte`, `lte`, `between`, `not_between`, `in`, `not_in`, `contains`, `not_contains`, `null`, `not_null`)
+///
+/// Only a conjunction of conditions can be expressed, `Or` is reported as unsupported.
+#[derive(Debug, Clone)]
+pub struct UrlParamsEncoder {
+    pub separator: String,
+}
+
+impl Default for UrlParamsEncoder {
+    fn default() -> Self {
+        Self { separator: "__".to_string() }
+    }
+}
+
+impl FilterEncoder for UrlParamsEncoder {
+    fn encode(&self, expr: &FilterExpr) -> Result<String, FilterEncodeError> {
+        let mut params = Vec::new();
+        self.collect(expr, false, &mut params)?;
+        Ok(params
+            .into_iter()
+            .map(|(key, value)| format!("{}={}", encode_query_key(&key), encode_query_component(&value)))
+            .collect::<Vec<_>>()
+            .join("&"))
+    }
+}
+
+impl UrlParamsEncoder {
+    fn collect(&self, expr: &FilterExpr, negated: bool, params: &mut Vec<(String, String)>) -> Result<(), FilterEncodeError> {
+        let mut push = |field: &str, op: &str, value: String| {
+            let key = match op.is_empty() {
+                true => field.to_string(),
+                false => format!("{}{}{}", field, self.separator, op),
+            };
+            params.push((key, value));
+        };
+        let list = |values: &[Value]| values.iter().map(json_to_text).collect::<Vec<_>>().join(",");
+        match (expr, negated) {
+            (FilterExpr::And(exprs), false) => {
+                for expr in exprs {
+                    self.collect(expr, false, params)?;
+                }
+            }
+            (FilterExpr::Or(exprs), true) => {
+                for expr in exprs {
+                    self.collect(expr, true, params)?;
+                }
+            }
+            (FilterExpr::Or(_), false) | (FilterExpr::And(_), true) => {
+                return Err(FilterEncodeError("url params can not express a disjunction".to_string()))
+            }
+            (FilterExpr::Not(expr), negated) => self.collect(expr, !negated, params)?,
+            (FilterExpr::Eq { field, value }, false) | (FilterExpr::Ne { field, value }, true) => push(field, "", json_to_text(value)),
+            (FilterExpr::Ne { field, value }, false) | (FilterExpr::Eq { field, value }, true) => push(field, "ne", json_to_text(value)),
+            (FilterExpr::Lt { field, value }, false) | (FilterExpr::Gte { field, value }, true) => push(field, "lt", json_to_text(value)),
+            (FilterExpr::Lt { field, value }, true) | (FilterExpr::Gte { field, value }, false) => push(field, "gte", json_to_text(value)),
+            (FilterExpr::Gt { field, value }, false) | (FilterExpr::Lte { field, value }, true) => push(field, "gt", json_to_text(value)),
+            (FilterExpr::Gt { field, value }, true) | (FilterExpr::Lte { field, value }, false) => push(field, "lte", json_to_text(value)),
+            (FilterExpr::Between { field, from, to }, false) => push(field, "between", list(&[from.clone(), to.clone()])),
+            (FilterExpr::Between { field, from, to }, true) => push(field, "not_between", list(&[from.clone(), to.clone()])),
+            (FilterExpr::In { field, values }, false) => push(field, "in", list(values)),
+            (FilterExpr::In { field, values }, true) => push(field, "not_in", list(values)),
+            (FilterExpr::Contains { field, value }, false) => push(field, "contains", value.clone()),
+            (FilterExpr::Contains { field, value }, true) => push(field, "not_contains", value.clone()),
+            (FilterExpr::IsNull { field }, false) => push(field, "null", "true".to_string()),
+            (FilterExpr::IsNull { field }, true) => push(field, "not_null", "true".to_string()),
         }
+        Ok(())
+    }
+}
+
+/// Percent encode a key, keeping the brackets readable
+fn encode_query_key(key: &str) -> String {
+    key.split_inclusive(['[', ']'])
+        .map(|part| match part.strip_suffix(['[', ']']) {
+            Some(text) => format!("{}{}", encode_query_component(text), &part[text.len()..]),
+            None => encode_query_component(part),
+        })
+        .collect()
+}
+
+/// Percent encode everything except the unreserved characters of RFC 3986
+fn encode_query_component(text: &str) -> String {
+    text.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+            b => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// The direction of a sorted column
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum SortDirection {
+    #[serde(rename = "asc")]
+    Ascending,
+    #[serde(rename = "desc")]
+    Descending,
+}
+
+impl SortDirection {
+    /// The next direction when clicking a header, ascending -> descending -> not sorted
+    pub fn next(self) -> Option<SortDirection> {
+        match self {
+            SortDirection::Ascending => Some(SortDirection::Descending),
+            SortDirection::Descending => None,
+        }
+    }
+}
+
+/// A struct representing a sorted column
+///
+/// # Arguments
+///
+/// * `sort_name` - The `sort_name` of the header to sort by
+/// * `direction` - The direction of the sort
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct SortSpec {
+    pub sort_name: String,
+    pub direction: SortDirection,
+}
+
+impl SortSpec {
+    pub fn new(sort_name: &str, direction: SortDirection) -> Self {
+        Self {
+            sort_name: sort_name.to_string(),
+            direction,
+        }
+    }
+
+    pub fn is_descending(&self) -> bool {
+        self.direction == SortDirection::Descending
+    }
+}
+
+/// A struct for the query of a single page of the table, used by a `DataSource`
+///
+/// # Arguments
+///
+/// * `offset` - The start of the rows to fetch
+/// * `limit` - The number of rows to fetch
+/// * `sort` - The sorted columns, by priority
+/// * `search` - The search keyword
+/// * `filter` - The filter to be used in fetching
+/// * `filters` - The filters of the columns
+/// * `condition` - An extra filter expression on the rows
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct TableQuery {
+    pub offset: u32,
+    pub limit: u32,
+    pub sort: Vec<SortSpec>,
+    pub search: String,
+    pub filter: String,
+    #[serde(default)]
+    pub filters: Vec<ColumnFilter>,
+    #[serde(default)]
+    pub condition: Option<FilterExpr>,
+}
+
+impl TableQuery {
+    /// The column filters and the condition combined in one expression, `None` when there is nothing to filter
+    pub fn filter_expr(&self) -> Option<FilterExpr> {
+        combine_filters(&self.filters, &self.condition)
+    }
+
+    /// The sort as a comma separated list of `sort_name`, descending ones prefixed by `-` (e.g. `name,-profit_amount`)
+    pub fn sort_string(&self) -> String {
+        sort_string(&self.sort)
     }
 }
 
@@ -83,10 +1299,549 @@ impl TableHeader {
 /// * `filter` - The filter to be used in downloading
 /// * `fields` - The fields to be downloaded
 /// * `search` - The search to be used in downloading
-#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+/// * `filters` - The filters of the columns to be used in downloading
+/// * `condition` - An extra filter expression to be used in downloading
+/// * `scope` - The rows to be downloaded, `ExportScope::Filtered`, `ExportScope::All` (the search and the filters are empty for `All`)
+///   or `ExportScope::Selected` when every row matching the query is selected (server mode, "Select all" on every page)
+/// * `excluded` - The keys of the rows deselected from a `Selected` scope, the back end skips them (see `BulkSelection::AllMatching`)
+/// * `sort` - The sorted columns, by priority
+/// * `columns` - The visible columns of the file, in order
+/// * `format` - The format of the file, binary formats (XLSX) are returned base64 encoded (see `export::encode_base64`)
+/// * `timezone` - The IANA time zone of the browser (e.g. `Europe/Paris`), empty when unknown, applied by `headers` to the dates
+///   of the columns without a `DateFormat` or with `DateZone::Local`
+/// * `locale` - The BCP 47 locale of the browser (e.g. `fr-FR`), empty when unknown (see `Locale::from_tag`), applied by `headers`
+///   to the numbers of the columns without a `NumberFormat` or a `CurrencyFormat`
+///
+/// # Example
+///
+/// ```ignore
+/// let request = DownloadDataRequest::from_state(&state, &headers.get_untracked())
+///     .table_name("trade")
+///     .format(ExportFormat::Xlsx);
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 pub struct DownloadDataRequest {
     pub table_name: String,
     pub filter: String,
     pub fields: String,
     pub search: String,
-}
\ No newline at end of file
+    #[serde(default)]
+    pub filters: Vec<ColumnFilter>,
+    #[serde(default)]
+    pub condition: Option<FilterExpr>,
+    #[serde(default)]
+    pub scope: ExportScope,
+    #[serde(default)]
+    pub excluded: Vec<String>,
+    #[serde(default)]
+    pub sort: Vec<SortSpec>,
+    #[serde(default)]
+    pub columns: Vec<ExportColumn>,
+    #[serde(default)]
+    pub format: ExportFormat,
+    #[serde(default)]
+    pub timezone: String,
+    #[serde(default)]
+    pub locale: String,
+}
+
+impl DownloadDataRequest {
+    /// A request of the filtered rows from the current state of a table, sorted like the table, with the headers as the columns
+    /// (kinds and formats included) and the time zone and the locale of the browser as the fallbacks of the formats
+    pub fn from_state(state: &TableState, headers: &[TableHeader]) -> Self {
+        let query = state.query_untracked();
+        Self {
+            filter: query.filter,
+            search: query.search,
+            filters: query.filters,
+            condition: query.condition,
+            sort: query.sort,
+            columns: headers.iter().map(ExportColumn::from).collect(),
+            timezone: browser_time_zone(),
+            locale: browser_locale(),
+            ..Default::default()
+        }
+    }
+
+    pub fn table_name(mut self, table_name: &str) -> Self {
+        self.table_name = table_name.to_string();
+        self
+    }
+
+    pub fn fields(mut self, fields: &str) -> Self {
+        self.fields = fields.to_string();
+        self
+    }
+
+    /// The rows to be downloaded, the search and the filters are cleared for `ExportScope::All`
+    pub fn scope(mut self, scope: ExportScope) -> Self {
+        self.scope = scope;
+        if scope == ExportScope::All {
+            self.filter.clear();
+            self.search.clear();
+            self.filters.clear();
+            self.condition = None;
+        }
+        self
+    }
+
+    /// The rows selected on every page (server mode, "Select all"): the `Selected` scope with the keys of the deselected rows
+    pub fn selected(mut self, selection: &RowSelection) -> Self {
+        self.scope = ExportScope::Selected;
+        self.excluded = untrack(|| selection.excluded_keys());
+        self
+    }
+
+    pub fn format(mut self, format: ExportFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// The column filters and the condition combined in one expression, `None` when there is nothing to filter
+    pub fn filter_expr(&self) -> Option<FilterExpr> {
+        combine_filters(&self.filters, &self.condition)
+    }
+
+    /// The sort as a comma separated list of `sort_name`, descending ones prefixed by `-` (e.g. `name,-profit_amount`)
+    pub fn sort_string(&self) -> String {
+        sort_string(&self.sort)
+    }
+
+    /// The headers of the columns with their kind and formats, `None` when no column is given
+    ///
+    /// The `locale` formats the numeric columns without a `NumberFormat` or a `CurrencyFormat`, and the `timezone` is the zone
+    /// of the dates without a `DateFormat` or with `DateZone::Local` (see `DateZone::Named`)
+    pub fn headers(&self) -> Option<Vec<TableHeader>> {
+        if self.columns.is_empty() {
+            return None;
+        }
+        let locale = Locale::from_tag(&self.locale);
+        let zone = match self.timezone.trim() {
+            "" => None,
+            timezone => Some(DateZone::named(timezone)),
+        };
+        let headers = self.columns.iter().map(|column| {
+            let mut header = column.header();
+            if header.kind.is_numeric() && header.number_format.is_none() && header.currency_format.is_none() {
+                header.number_format = locale.map(NumberFormat::locale);
+            }
+            if let Some(zone) = &zone {
+                match &mut header.date_format {
+                    Some(date_format) if date_format.zone == DateZone::Local => date_format.zone = zone.clone(),
+                    Some(_) => {}
+                    None if matches!(header.kind, ColumnKind::Date | ColumnKind::DateTime) => {
+                        header.date_format = Some(DateFormat::default().zone(zone.clone()));
+                    }
+                    None => {}
+                }
+            }
+            header
+        });
+        Some(headers.collect())
+    }
+}
+
+/// A column of a downloaded file
+///
+/// # Arguments
+///
+/// * `name` - The `name` of the header, the field (or nested path) of the rows
+/// * `display_name` - The `display_name` of the header, the header of the column in the file
+/// * `kind` - The `kind` of the header, numbers, booleans and dates are typed in the file
+/// * `currency` - The field of the currency code of the amounts, empty when the header is not a currency
+/// * `number_format` - The `number_format` of the header
+/// * `currency_format` - The `currency_format` of the header
+/// * `date_format` - The `date_format` of the header, its zone wins over the `timezone` of the request
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ExportColumn {
+    pub name: String,
+    pub display_name: String,
+    #[serde(default)]
+    pub kind: ColumnKind,
+    #[serde(default)]
+    pub currency: String,
+    #[serde(default)]
+    pub number_format: Option<NumberFormat>,
+    #[serde(default)]
+    pub currency_format: Option<CurrencyFormat>,
+    #[serde(default)]
+    pub date_format: Option<DateFormat>,
+}
+
+impl ExportColumn {
+    /// The header of the column, with the kind and the formats of the header it was built from
+    pub fn header(&self) -> TableHeader {
+        let mut header = TableHeader::of_kind(&self.name, self.kind.clone()).label(&self.display_name);
+        if !self.currency.is_empty() {
+            header = header.currency_from(&self.currency);
+        }
+        header.number_format = self.number_format.clone();
+        header.currency_format = self.currency_format.clone();
+        header.date_format = self.date_format.clone();
+        header
+    }
+}
+
+impl From<&TableHeader> for ExportColumn {
+    fn from(header: &TableHeader) -> Self {
+        Self {
+            name: header.name.clone(),
+            display_name: header.display_name.clone(),
+            kind: header.kind.clone(),
+            currency: match header.has_currency() {
+                true => header.currency.clone(),
+                false => String::new(),
+            },
+            number_format: header.number_format.clone(),
+            currency_format: header.currency_format.clone(),
+            date_format: header.date_format.clone(),
+        }
+    }
+}
+
+/// The time zone resolved by `Intl.DateTimeFormat`, empty outside of the browser
+fn browser_time_zone() -> String {
+    resolved_date_time_option("timeZone")
+}
+
+/// The locale resolved by `Intl.DateTimeFormat`, empty outside of the browser
+fn browser_locale() -> String {
+    resolved_date_time_option("locale")
+}
+
+#[cfg(target_arch = "wasm32")]
+fn resolved_date_time_option(key: &str) -> String {
+    use web_sys::js_sys::{Array, Intl, Object, Reflect};
+    let options = Intl::DateTimeFormat::new(&Array::new(), &Object::new()).resolved_options();
+    Reflect::get(&options, &key.into()).ok().and_then(|value| value.as_string()).unwrap_or_default()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn resolved_date_time_option(_key: &str) -> String {
+    String::new()
+}
+
+fn sort_string(sort: &[SortSpec]) -> String {
+    sort.iter()
+        .map(|s| match s.direction {
+            SortDirection::Ascending => s.sort_name.clone(),
+            SortDirection::Descending => format!("-{}", s.sort_name),
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn combine_filters(filters: &[ColumnFilter], condition: &Option<FilterExpr>) -> Option<FilterExpr> {
+    let mut exprs = filters.iter().map(ColumnFilter::to_expr).collect::<Vec<_>>();
+    exprs.extend(condition.clone());
+    match exprs.len() {
+        0 => None,
+        1 => exprs.pop(),
+        _ => Some(FilterExpr::And(exprs)),
+    }
+}
+#[cfg(test)]
+mod tests {
+    use leptos::create_runtime;
+    use serde_json::json;
+
+    use super::*;
+    use crate::selection::SelectionMode;
+
+    #[test]
+    fn matches_comparisons() {
+        let row = json!({"amount": 10, "name": "Alice", "price": "2.5", "tags": ["a", "b"], "empty": null});
+        assert!(FilterExpr::equals("amount", 10).matches(&row));
+        assert!(FilterExpr::equals("amount", "10").matches(&row));
+        assert!(!FilterExpr::equals("name", "alice").matches(&row));
+        assert!(FilterExpr::not_equals("name", "Bob").matches(&row));
+        assert!(FilterExpr::less_than("price", 3).matches(&row));
+        assert!(FilterExpr::greater_than("amount", 9).matches(&row));
+        assert!(FilterExpr::at_least("amount", 10).matches(&row));
+        assert!(!FilterExpr::at_least("amount", 11).matches(&row));
+        assert!(FilterExpr::at_most("amount", 10).matches(&row));
+        assert!(FilterExpr::between("amount", 10, 20).matches(&row));
+        assert!(FilterExpr::is_in("name", vec![json!("Bob"), json!("Alice")]).matches(&row));
+        assert!(FilterExpr::contains("name", "LIC").matches(&row));
+        assert!(FilterExpr::contains("tags", "b").matches(&row));
+        assert!(FilterExpr::is_null("empty").matches(&row));
+        assert!(FilterExpr::is_null("missing").matches(&row));
+    }
+
+    #[test]
+    fn resolve_paths() {
+        let row = json!({
+            "name": "Alice",
+            "user.name": "flat",
+            "user": {"profile": {"name": "Bob"}},
+            "items": [{"price": 1}, {"price": 2, "tags": ["a", "b"]}],
+            "matrix": [[1, 2], [3, 4]]
+        });
+        assert_eq!(resolve_path(&row, "name"), Some(&json!("Alice")));
+        assert_eq!(resolve_path(&row, "user.name"), Some(&json!("flat")));
+        assert_eq!(resolve_path(&row, "user.profile.name"), Some(&json!("Bob")));
+        assert_eq!(resolve_path(&row, "items[1].price"), Some(&json!(2)));
+        assert_eq!(resolve_path(&row, "items.0.price"), Some(&json!(1)));
+        assert_eq!(resolve_path(&row, "items[1].tags[0]"), Some(&json!("a")));
+        assert_eq!(resolve_path(&row, "matrix[1][0]"), Some(&json!(3)));
+        assert_eq!(resolve_path(&row, "/user/profile/name"), Some(&json!("Bob")));
+        assert_eq!(resolve_path(&row, "/items/0/price"), Some(&json!(1)));
+        assert_eq!(resolve_path(&row, "items[5].price"), None);
+        assert_eq!(resolve_path(&row, "items[x]"), None);
+        assert_eq!(resolve_path(&row, "user.missing"), None);
+        assert_eq!(resolve_path(&row, ""), None);
+    }
+
+    #[test]
+    fn matches_groups() {
+        let row = json!({"amount": 10, "name": "Alice"});
+        assert!(FilterExpr::all(vec![FilterExpr::equals("name", "Alice"), FilterExpr::greater_than("amount", 5)]).matches(&row));
+        assert!(!FilterExpr::all(vec![FilterExpr::equals("name", "Alice"), FilterExpr::greater_than("amount", 50)]).matches(&row));
+        assert!(FilterExpr::any(vec![FilterExpr::equals("name", "Bob"), FilterExpr::greater_than("amount", 5)]).matches(&row));
+        assert!(FilterExpr::equals("name", "Bob").negate().matches(&row));
+        assert!(FilterExpr::all(Vec::new()).matches(&row));
+        assert!(!FilterExpr::any(Vec::new()).matches(&row));
+    }
+
+    #[test]
+    fn open_ranges_exclude_nulls() {
+        let null_row = json!({"amount": null});
+        let filter = |min, max| ColumnFilter {
+            field: "amount".to_string(),
+            value: ColumnFilterValue::NumberRange { min, max },
+        };
+        assert!(!filter(Some(1.0), None).to_expr().matches(&null_row));
+        assert!(!filter(None, Some(1.0)).to_expr().matches(&null_row));
+        assert!(filter(Some(1.0), None).to_expr().matches(&json!({"amount": 1})));
+        assert!(filter(None, Some(1.0)).to_expr().matches(&json!({"amount": 1})));
+    }
+
+    #[test]
+    fn date_range_includes_the_last_day() {
+        let filter = ColumnFilter {
+            field: "created".to_string(),
+            value: ColumnFilterValue::DateRange {
+                from: Some("2024-01-01".to_string()),
+                to: Some("2024-01-31".to_string()),
+            },
+        };
+        let expr = filter.to_expr();
+        assert!(expr.matches(&json!({"created": "2024-01-01"})));
+        assert!(expr.matches(&json!({"created": "2024-01-31T23:59:59Z"})));
+        assert!(!expr.matches(&json!({"created": "2024-02-01T00:00:00Z"})));
+        assert!(!expr.matches(&json!({"created": "2023-12-31T23:59:59Z"})));
+    }
+
+    #[test]
+    fn mixed_text_column_sorts_in_total_order() {
+        let values = ["b", "10", "A", "2", "", "a", "NaN", "-1.5", "x10", "2.0", "10"];
+        for kind in [ColumnKind::Text, ColumnKind::Integer, ColumnKind::Date, ColumnKind::Enum(vec!["a".to_string(), "2".to_string()])] {
+            for a in values {
+                for b in values {
+                    assert_eq!(kind.compare(a, b), kind.compare(b, a).reverse(), "{:?} {} {}", kind, a, b);
+                    for c in values {
+                        if kind.compare(a, b).is_le() && kind.compare(b, c).is_le() {
+                            assert!(kind.compare(a, c).is_le(), "{:?} {} {} {}", kind, a, b, c);
+                        }
+                    }
+                }
+            }
+        }
+        let mut sorted = values.to_vec();
+        sorted.sort_by(|a, b| ColumnKind::Text.compare(a, b));
+        assert_eq!(sorted, vec!["-1.5", "2", "2.0", "10", "10", "NaN", "", "A", "a", "b", "x10"]);
+        let mut sorted = values.to_vec();
+        sorted.sort_by(|a, b| ColumnKind::Integer.compare(a, b));
+        assert_eq!(&sorted[..5], &["-1.5", "2", "2.0", "10", "10"]);
+    }
+
+    #[test]
+    fn legacy_currency_header_renders_as_text() {
+        let header = TableHeader::new("price", "price", "Price", true, "currency", false, "", "", "", false, None);
+        assert_eq!(header.kind, ColumnKind::Text);
+        assert!(header.has_currency());
+        assert_eq!(header.display_value(&json!({"price": 1234, "currency": "JPY"})), "1234");
+        assert_eq!(header.display_value(&json!({"price": 1.234, "currency": "BHD"})), "1.234");
+    }
+
+    #[test]
+    fn validate_currency_kind_without_field() {
+        assert_eq!(
+            TableHeader::currency("price").validate(),
+            Err(vec![HeaderConfigError::MissingCurrencyField("price".to_string())])
+        );
+        assert_eq!(TableHeader::currency("price").currency_from("code").validate(), Ok(()));
+    }
+
+    #[test]
+    fn exports_keep_full_precision() {
+        assert_eq!(ColumnKind::Integer.export("1.6"), Some("1.6".to_string()));
+        assert_eq!(ColumnKind::Decimal { precision: 2 }.export("0.125"), Some("0.125".to_string()));
+        assert_eq!(ColumnKind::Currency.export("1.234"), Some("1.234".to_string()));
+        assert_eq!(ColumnKind::Currency.export("abc"), None);
+    }
+
+    #[test]
+    fn safe_hrefs() {
+        for href in ["https://example.com", "HTTP://example.com", "mailto:a@example.com", "/users/1", "users/1?a=b:c", "#top", "//example.com", ""] {
+            assert!(is_safe_href(href), "{}", href);
+        }
+        for href in ["javascript:alert(1)", " JavaScript:alert(1)", "java\tscript:alert(1)", "data:text/html,x", "vbscript:x"] {
+            assert!(!is_safe_href(href), "{}", href);
+        }
+    }
+
+    #[test]
+    fn directus_encoding() {
+        let expr = FilterExpr::all(vec![FilterExpr::equals("status", "Gain"), FilterExpr::at_least("profit", 100)]);
+        assert_eq!(
+            DirectusEncoder.encode(&expr).unwrap(),
+            "filter[_and][0][status][_eq]=Gain&filter[_and][1][profit][_gte]=100"
+        );
+        let expr = FilterExpr::any(vec![FilterExpr::less_than("price", 5), FilterExpr::is_null("price")]).negate();
+        assert_eq!(
+            DirectusEncoder.to_value(&expr),
+            json!({"_and": [{"price": {"_gte": 5}}, {"price": {"_nnull": true}}]})
+        );
+        assert_eq!(
+            DirectusEncoder.encode(&FilterExpr::contains("user name", "a&b")).unwrap(),
+            "filter[user%20name][_icontains]=a%26b"
+        );
+    }
+
+    #[test]
+    fn url_params_encoding() {
+        let expr = FilterExpr::all(vec![
+            FilterExpr::equals("status", "Gain"),
+            FilterExpr::at_most("profit", 100),
+            FilterExpr::between("age", 18, 30),
+            FilterExpr::is_in("id", vec![json!(1), json!(2)]).negate(),
+        ]);
+        assert_eq!(
+            UrlParamsEncoder::default().encode(&expr).unwrap(),
+            "status=Gain&profit__lte=100&age__between=18%2C30&id__not_in=1%2C2"
+        );
+        assert_eq!(
+            UrlParamsEncoder::default().encode(&FilterExpr::any(vec![FilterExpr::is_null("a")])),
+            Err(FilterEncodeError("url params can not express a disjunction".to_string()))
+        );
+    }
+
+    #[test]
+    fn arrays_objects_and_nulls_as_text() {
+        let row = json!({
+            "tags": ["a", 1, true, null],
+            "empty": [],
+            "owner": {"name": "Alice", "address": {"city": "Paris"}},
+            "note": null,
+        });
+        assert_eq!(TableHeader::text("tags").find(&row), "a, 1, true");
+        assert_eq!(TableHeader::text("tags").joined(" | ").find(&row), "a | 1 | true");
+        assert_eq!(TableHeader::text("tags").chips().find_items(&row), Some(vec!["a".to_string(), "1".to_string(), "true".to_string()]));
+        assert_eq!(TableHeader::text("owner").find_items(&row), None);
+        assert_eq!(TableHeader::text("empty").default_value("-").find(&row), "-");
+        assert_eq!(TableHeader::text("empty").find_items(&row), Some(Vec::new()));
+        assert_eq!(TableHeader::text("owner").object_key("name").find(&row), "Alice");
+        assert_eq!(TableHeader::text("owner").object_key("address.city").find(&row), "Paris");
+        assert_eq!(TableHeader::text("owner").object_key("missing").default_value("-").find(&row), "-");
+        assert_eq!(TableHeader::text("owner.address").find(&row), r#"{"city":"Paris"}"#);
+        let note = TableHeader::text("note").null_as("n/a").default_value("-");
+        assert_eq!(note.find(&row), "n/a");
+        assert_eq!(note.find(&json!({})), "-");
+        assert!(note.is_null(&row));
+        assert!(!note.is_null(&json!({})));
+        assert_eq!(TableHeader::text("note").default_value("-").find(&row), "-");
+    }
+
+    #[test]
+    fn is_null_matches_null_and_missing_fields() {
+        let expr = FilterExpr::is_null("note");
+        assert!(expr.matches(&json!({"note": null})));
+        assert!(expr.matches(&json!({})));
+        assert!(!expr.matches(&json!({"note": ""})));
+        assert!(!expr.clone().negate().matches(&json!({"note": null})));
+        assert!(expr.negate().matches(&json!({"note": "x"})));
+    }
+
+    #[test]
+    fn legacy_styles_use_the_theme() {
+        let header = TableHeader::new("profit", "profit", "Profit", false, "", true, "", "", "", false, None);
+        let (daisy_ui, tailwind) = (TableTheme::daisy_ui(), TableTheme::tailwind());
+        assert_eq!(header.cell_class(&json!({"profit": 5}), &daisy_ui).trim(), "text-success");
+        assert_eq!(header.cell_class(&json!({"profit": -5}), &tailwind).trim(), "text-red-600");
+        let status = TableHeader::new("status", "status", "Status", false, "", false, "", "Gain", "Loss", false, None);
+        assert_eq!(status.cell_class(&json!({"status": "Gain"}), &tailwind).trim(), "text-green-600");
+        assert_eq!(status.cell_class(&json!({"status": "Loss"}), &tailwind).trim(), "text-red-600");
+    }
+
+    #[test]
+    fn selected_request_carries_the_excluded_keys() {
+        let runtime = create_runtime();
+        let state = TableState::new(2);
+        state.set_search("john");
+        let selection = RowSelection::new("id", SelectionMode::Multiple);
+        selection.select_all_pages(&[json!({"id": 1}), json!({"id": 2})]);
+        selection.set_selected(&json!({"id": 3}), false);
+        let request = DownloadDataRequest::from_state(&state, &[TableHeader::text("id")]).selected(&selection);
+        assert_eq!(request.scope, ExportScope::Selected);
+        assert_eq!(request.excluded, vec!["3".to_string()]);
+        assert_eq!(request.search, "john");
+        runtime.dispose();
+    }
+
+    #[test]
+    fn request_headers_keep_kinds_and_formats() {
+        let headers = [
+            TableHeader::currency("profit").label("Profit").currency_from("code").currency_format(CurrencyFormat::default()),
+            TableHeader::of_kind("closed_at", ColumnKind::DateTime).date_format(DateFormat::default().zone(DateZone::Utc)),
+        ];
+        let request = DownloadDataRequest { columns: headers.iter().map(ExportColumn::from).collect(), ..Default::default() };
+        let request: DownloadDataRequest = serde_json::from_str(&serde_json::to_string(&request).unwrap()).unwrap();
+        let rebuilt = request.headers().unwrap();
+        let row = json!({"profit": 1234.5, "code": "USD", "closed_at": "2024-03-01T23:30:00+02:00"});
+        assert_eq!(rebuilt[0].kind, ColumnKind::Currency);
+        assert_eq!(rebuilt[0].export_value(&row), headers[0].export_value(&row));
+        assert_eq!(rebuilt[1].find_datetime(&row), headers[1].find_datetime(&row));
+        assert_eq!(rebuilt[1].display_value(&row), headers[1].display_value(&row));
+    }
+
+    #[test]
+    fn request_headers_fall_back_to_the_timezone_and_locale() {
+        let runtime = create_runtime();
+        let headers = [
+            TableHeader::decimal("price", 2),
+            TableHeader::decimal("fee", 2).number_format(NumberFormat::locale(Locale::FrFr)),
+            TableHeader::of_kind("opened_at", ColumnKind::DateTime),
+            TableHeader::of_kind("closed_at", ColumnKind::DateTime).date_format(DateFormat::default().zone(DateZone::Utc)),
+            TableHeader::of_kind("due", ColumnKind::Date).date_format(DateFormat::default().pattern("%d/%m/%Y")),
+            TableHeader::text("name"),
+        ];
+        let mut request = DownloadDataRequest::from_state(&TableState::new(10), &headers);
+        request.timezone = "Europe/Paris".to_string();
+        request.locale = "de-DE".to_string();
+        let rebuilt = request.headers().unwrap();
+        assert_eq!(rebuilt[0].number_format, Some(NumberFormat::locale(Locale::DeDe)));
+        assert_eq!(rebuilt[1].number_format, Some(NumberFormat::locale(Locale::FrFr)));
+        assert_eq!(rebuilt[2].date_format, Some(DateFormat::default().zone(DateZone::named("Europe/Paris"))));
+        assert_eq!(rebuilt[3].date_format.as_ref().map(|format| &format.zone), Some(&DateZone::Utc));
+        assert_eq!(rebuilt[4].date_format, Some(DateFormat::default().pattern("%d/%m/%Y").zone(DateZone::named("Europe/Paris"))));
+        assert_eq!((rebuilt[5].number_format.as_ref(), rebuilt[5].date_format.as_ref()), (None, None));
+        request.timezone.clear();
+        request.locale.clear();
+        let rebuilt = request.headers().unwrap();
+        assert_eq!((rebuilt[0].number_format.as_ref(), rebuilt[2].date_format.as_ref()), (None, None));
+        runtime.dispose();
+    }
+
+    #[test]
+    fn export_column_keeps_non_currency_columns() {
+        let mut header = TableHeader::text("note");
+        header.currency = "code".to_string();
+        let column = ExportColumn::from(&header);
+        assert_eq!(column.currency, "");
+        assert!(!column.header().has_currency());
+        let column = ExportColumn::from(&TableHeader::decimal("price", 2).currency_from("code"));
+        assert_eq!(column.currency, "code");
+        assert!(column.header().has_currency());
+    }
+}
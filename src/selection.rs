@@ -0,0 +1,337 @@
+use leptos::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::model::{resolve_path, TableQuery};
+
+/// How many rows can be selected at once, `None` (default) hides the selection column
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SelectionMode {
+    #[default]
+    None,
+    Single,
+    Multiple,
+}
+
+/// The selected rows of a table, identified by the value of `key_field` in each row
+///
+/// The selection is kept by key, so it stays stable across sorting and pagination.
+/// In server mode every row matching the query can be selected at once with `select_all_pages`,
+/// the keys of the rows on the other pages are then unknown, `is_all_pages` tells the backend to act on the whole query
+/// except the rows deselected since, `excluded_keys`. Changing the search or the filters leaves this mode.
+/// Rows without a key can not be selected.
+///
+/// # Example
+///
+/// ```ignore
+/// let selection = RowSelection::new("trade_id", SelectionMode::Multiple);
+/// let selected_keys: RwSignal<Vec<String>> = selection.keys();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RowSelection {
+    mode: SelectionMode,
+    key_field: StoredValue<String>,
+    keys: RwSignal<Vec<String>>,
+    rows: RwSignal<Vec<Value>>,
+    all_pages: RwSignal<bool>,
+    excluded: RwSignal<Vec<String>>,
+}
+
+impl Default for RowSelection {
+    fn default() -> Self {
+        Self::new("", SelectionMode::None)
+    }
+}
+
+impl RowSelection {
+    pub fn new(key_field: &str, mode: SelectionMode) -> Self {
+        Self {
+            mode,
+            key_field: store_value(key_field.to_string()),
+            keys: RwSignal::new(Vec::new()),
+            rows: RwSignal::new(Vec::new()),
+            all_pages: RwSignal::new(false),
+            excluded: RwSignal::new(Vec::new()),
+        }
+    }
+
+    pub fn mode(&self) -> SelectionMode {
+        self.mode
+    }
+
+    /// The selection column is shown
+    pub fn is_enabled(&self) -> bool {
+        self.mode != SelectionMode::None
+    }
+
+    /// The keys of the selected rows
+    pub fn keys(&self) -> RwSignal<Vec<String>> {
+        self.keys
+    }
+
+    /// The key of a row, strings and numbers are used as is, the key field can be a nested path
+    pub fn key_of(&self, row: &Value) -> Option<String> {
+        self.key_field.with_value(|key_field| match resolve_path(row, key_field) {
+            Some(Value::String(s)) => Some(s.clone()),
+            Some(Value::Number(n)) => Some(n.to_string()),
+            Some(Value::Bool(b)) => Some(b.to_string()),
+            _ => None,
+        })
+    }
+
+    /// Every row matching the query is selected, including the rows on the other pages
+    pub fn is_all_pages(&self) -> bool {
+        self.all_pages.get()
+    }
+
+    /// The keys of the rows deselected while every page is selected
+    pub fn excluded_keys(&self) -> Vec<String> {
+        self.excluded.get()
+    }
+
+    pub fn is_selected(&self, row: &Value) -> bool {
+        let Some(key) = self.key_of(row) else {
+            return false;
+        };
+        match self.all_pages.get() {
+            true => self.excluded.with(|excluded| !excluded.contains(&key)),
+            false => self.keys.with(|keys| keys.contains(&key)),
+        }
+    }
+
+    /// Every row of the slice is selected (false when empty)
+    pub fn is_all_selected(&self, rows: &[Value]) -> bool {
+        !rows.is_empty() && rows.iter().all(|row| self.is_selected(row))
+    }
+
+    /// The number of selected rows, `total` without the excluded rows when every row matching the query is selected
+    pub fn count(&self, total: u32) -> u32 {
+        match self.all_pages.get() {
+            true => total.saturating_sub(self.excluded.with(|excluded| excluded.len() as u32)),
+            false => self.keys.with(|keys| keys.len() as u32),
+        }
+    }
+
+    /// The selected rows that have been displayed, in order of selection
+    pub fn selected_rows(&self) -> Vec<Value> {
+        if self.all_pages.get() {
+            return self.rows.with(|rows| rows.iter().filter(|row| self.is_selected(row)).cloned().collect());
+        }
+        self.keys.with(|keys| {
+            self.rows.with(|rows| {
+                keys.iter()
+                    .filter_map(|key| rows.iter().find(|row| self.key_of(row).as_ref() == Some(key)).cloned())
+                    .collect()
+            })
+        })
+    }
+
+    pub fn toggle(&self, row: &Value) {
+        let selected = untrack(|| self.is_selected(row));
+        self.set_selected(row, !selected);
+    }
+
+    /// Select or deselect a row, in single mode selecting a row replaces the selection.
+    /// Deselecting a row while every page is selected excludes it from the selection.
+    pub fn set_selected(&self, row: &Value, selected: bool) {
+        let Some(key) = self.key_of(row).filter(|_| self.is_enabled()) else {
+            return;
+        };
+        batch(|| {
+            if self.all_pages.get_untracked() {
+                self.excluded.update(|excluded| match selected {
+                    true => excluded.retain(|k| k != &key),
+                    false if !excluded.contains(&key) => excluded.push(key.clone()),
+                    false => {}
+                });
+            }
+            self.remember(std::slice::from_ref(row));
+            self.keys.update(|keys| match (selected, self.mode) {
+                (true, SelectionMode::Single) => *keys = vec![key],
+                (true, SelectionMode::Multiple) if !keys.contains(&key) => keys.push(key),
+                (true, _) => {}
+                (false, _) => keys.retain(|k| k != &key),
+            });
+        });
+    }
+
+    /// Select every row of the slice (e.g. the current page)
+    pub fn select_rows(&self, rows: &[Value]) {
+        if self.mode != SelectionMode::Multiple {
+            return;
+        }
+        let added = rows.iter().filter_map(|row| self.key_of(row)).collect::<Vec<_>>();
+        batch(|| {
+            self.remember(rows);
+            self.excluded.update(|excluded| excluded.retain(|k| !added.contains(k)));
+            self.keys.update(|keys| {
+                for key in added {
+                    if !keys.contains(&key) {
+                        keys.push(key);
+                    }
+                }
+            });
+        });
+    }
+
+    /// Deselect every row of the slice (e.g. the current page), excluding them when every page is selected
+    pub fn deselect_rows(&self, rows: &[Value]) {
+        let removed = rows.iter().filter_map(|row| self.key_of(row)).collect::<Vec<_>>();
+        batch(|| {
+            if self.all_pages.get_untracked() {
+                self.excluded.update(|excluded| {
+                    for key in &removed {
+                        if !excluded.contains(key) {
+                            excluded.push(key.clone());
+                        }
+                    }
+                });
+            }
+            self.keys.update(|keys| keys.retain(|k| !removed.contains(k)));
+        });
+    }
+
+    /// Select every row matching the query, including the rows on the other pages (server mode)
+    pub fn select_all_pages(&self, displayed_rows: &[Value]) {
+        if self.mode == SelectionMode::Multiple {
+            batch(|| {
+                self.excluded.set(Vec::new());
+                self.select_rows(displayed_rows);
+                self.all_pages.set(true);
+            });
+        }
+    }
+
+    /// Leave the selection of every page, keeping the rows selected one by one (e.g. when the query changes)
+    pub fn clear_all_pages(&self) {
+        batch(|| {
+            self.all_pages.set(false);
+            self.excluded.set(Vec::new());
+        });
+    }
+
+    pub fn clear(&self) {
+        batch(|| {
+            self.all_pages.set(false);
+            self.excluded.set(Vec::new());
+            self.keys.set(Vec::new());
+            self.rows.set(Vec::new());
+        });
+    }
+
+    /// Keep the latest value of the rows so the selected rows can be returned later, rows without a key are skipped
+    fn remember(&self, rows: &[Value]) {
+        self.rows.update(|remembered| {
+            for row in rows {
+                let Some(key) = self.key_of(row) else {
+                    continue;
+                };
+                match remembered.iter().position(|r| self.key_of(r).as_ref() == Some(&key)) {
+                    Some(i) => remembered[i] = row.clone(),
+                    None => remembered.push(row.clone()),
+                }
+            }
+        });
+    }
+}
+
+/// The rows a bulk action applies to
+///
+/// * `Rows` - The selected rows
+/// * `AllMatching` - Every row matching the query (server mode, "Select all" on every page), except the rows with the `excluded` keys
+#[derive(Debug, Clone, PartialEq)]
+pub enum BulkSelection {
+    Rows(Vec<Value>),
+    AllMatching { query: TableQuery, excluded: Vec<String> },
+}
+
+/// An action on the selected rows, shown in the toolbar of the table when rows are selected
+///
+/// # Arguments
+///
+/// * `label` - The label of the button
+/// * `icon` - Optional SVG path (`d` attribute, 20x20 view box) shown before the label
+/// * `confirm` - Optional confirmation message, the action is destructive and asks for confirmation first
+/// * `on_click` - Called with the selection, see `BulkSelection`
+///
+/// # Example
+///
+/// ```ignore
+/// let actions = vec![
+///     BulkAction::new("Export", move |selection| export_trades(selection)),
+///     BulkAction::new("Cancel", move |selection| match selection {
+///         BulkSelection::Rows(rows) => cancel_trades(rows),
+///         BulkSelection::AllMatching { query, excluded } => cancel_matching_trades(query, excluded),
+///     })
+///     .confirm("Cancel the selected trades?"),
+/// ];
+/// ```
+#[derive(Clone)]
+pub struct BulkAction {
+    pub label: String,
+    pub icon: Option<String>,
+    pub confirm: Option<String>,
+    pub on_click: Callback<BulkSelection>,
+}
+
+impl BulkAction {
+    pub fn new(label: &str, on_click: impl Fn(BulkSelection) + 'static) -> Self {
+        Self {
+            label: label.to_string(),
+            icon: None,
+            confirm: None,
+            on_click: Callback::new(on_click),
+        }
+    }
+
+    pub fn icon(mut self, svg_path: &str) -> Self {
+        self.icon = Some(svg_path.to_string());
+        self
+    }
+
+    /// Mark the action as destructive, asking for confirmation with this message first
+    pub fn confirm(mut self, message: &str) -> Self {
+        self.confirm = Some(message.to_string());
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn deselect_while_all_pages_are_selected() {
+        let runtime = create_runtime();
+        let selection = RowSelection::new("id", SelectionMode::Multiple);
+        let first_page = vec![json!({"id": 1}), json!({"id": 2})];
+        selection.select_all_pages(&first_page);
+        let other_page_row = json!({"id": 3});
+        assert!(selection.is_selected(&other_page_row));
+        selection.set_selected(&other_page_row, false);
+        assert!(selection.is_all_pages());
+        assert!(!selection.is_selected(&other_page_row));
+        assert!(selection.is_selected(&first_page[0]));
+        assert_eq!(selection.excluded_keys(), vec!["3".to_string()]);
+        assert_eq!(selection.count(10), 9);
+        selection.toggle(&other_page_row);
+        assert_eq!(selection.count(10), 10);
+        selection.clear_all_pages();
+        assert!(!selection.is_all_pages());
+        assert_eq!(selection.count(10), 3);
+        runtime.dispose();
+    }
+
+    #[test]
+    fn rows_without_key_are_not_selected() {
+        let runtime = create_runtime();
+        let selection = RowSelection::new("id", SelectionMode::Multiple);
+        let rows = vec![json!({"id": 1}), json!({"name": "a"}), json!({"name": "b"})];
+        selection.select_all_pages(&rows);
+        assert!(!selection.is_selected(&rows[1]));
+        assert_eq!(selection.selected_rows(), vec![rows[0].clone()]);
+        runtime.dispose();
+    }
+}
@@ -0,0 +1,104 @@
+use std::future::Future;
+
+use leptos::ServerFnError;
+use serde_json::Value;
+
+use crate::client;
+use crate::export::{self, CsvOptions};
+use crate::model::{DownloadDataRequest, TableQuery};
+
+/// A source of rows that the `DataSourceTable` can drive on its own
+///
+/// * `fetch_page` - The rows of a single page, sorted and searched
+/// * `fetch_count` - The total number of rows matching the search and filter of the query
+/// * `fetch_export` - The content of the file to download
+pub trait DataSource: 'static {
+    fn fetch_page(&self, query: TableQuery) -> impl Future<Output = Result<Vec<Value>, ServerFnError>>;
+    fn fetch_count(&self, query: TableQuery) -> impl Future<Output = Result<u32, ServerFnError>>;
+    fn fetch_export(&self, request: DownloadDataRequest) -> impl Future<Output = Result<String, ServerFnError>>;
+}
+
+/// Rows that are already loaded, searched across every field and sorted by field name.
+/// The column filters and the condition are applied, the filter string of the query is ignored.
+/// The export is sorted like the table, limited to the columns of the request when they are given, in the format of the request.
+impl DataSource for Vec<Value> {
+    async fn fetch_page(&self, query: TableQuery) -> Result<Vec<Value>, ServerFnError> {
+        let headers = client::headers_from_rows(self);
+        let rows = client::condition_rows(self.clone(), &query.condition);
+        let rows = client::filter_rows(&headers, rows, &query.filters);
+        let mut rows = client::search_rows(&headers, rows, &query.search);
+        client::sort_rows(&headers, &mut rows, &query.sort);
+        Ok(client::page_rows(&rows, query.offset, query.limit))
+    }
+
+    async fn fetch_count(&self, query: TableQuery) -> Result<u32, ServerFnError> {
+        let headers = client::headers_from_rows(self);
+        let rows = client::condition_rows(self.clone(), &query.condition);
+        let rows = client::filter_rows(&headers, rows, &query.filters);
+        Ok(client::search_rows(&headers, rows, &query.search).len() as u32)
+    }
+
+    async fn fetch_export(&self, request: DownloadDataRequest) -> Result<String, ServerFnError> {
+        let headers = client::headers_from_rows(self);
+        let rows = client::condition_rows(self.clone(), &request.condition);
+        let rows = client::filter_rows(&headers, rows, &request.filters);
+        let mut rows = client::search_rows(&headers, rows, &request.search);
+        client::sort_rows(&headers, &mut rows, &request.sort);
+        let content = request.format.render(&request.headers().unwrap_or(headers), &rows, &CsvOptions::default());
+        match request.format.is_binary() {
+            true => Ok(export::encode_base64(&content)),
+            false => Ok(String::from_utf8_lossy(&content).into_owned()),
+        }
+    }
+}
+
+/// A source backed by leptos server functions (or any async function)
+///
+/// # Arguments
+///
+/// * `page` - Fetch the rows of a page given the query
+/// * `count` - Fetch the total number of rows given the query
+/// * `export` - Fetch the content of the file to download given the download request
+///
+/// # Example
+///
+/// ```ignore
+/// let source = ServerFnSource::new(
+///     move |q: TableQuery| async move { get_collection_values(q).await },
+///     move |q: TableQuery| async move { get_collection_count(q.filter, Some(q.search)).await },
+///     move |d: DownloadDataRequest| async move { get_collection_file::<UserProfitResponse>(d).await },
+/// );
+/// ```
+pub struct ServerFnSource<P, C, E> {
+    page: P,
+    count: C,
+    export: E,
+}
+
+impl<P, C, E> ServerFnSource<P, C, E> {
+    pub fn new(page: P, count: C, export: E) -> Self {
+        Self { page, count, export }
+    }
+}
+
+impl<P, PF, C, CF, E, EF> DataSource for ServerFnSource<P, C, E>
+where
+    P: Fn(TableQuery) -> PF + 'static,
+    PF: Future<Output = Result<Vec<Value>, ServerFnError>>,
+    C: Fn(TableQuery) -> CF + 'static,
+    CF: Future<Output = Result<u32, ServerFnError>>,
+    E: Fn(DownloadDataRequest) -> EF + 'static,
+    EF: Future<Output = Result<String, ServerFnError>>,
+{
+    fn fetch_page(&self, query: TableQuery) -> impl Future<Output = Result<Vec<Value>, ServerFnError>> {
+        (self.page)(query)
+    }
+
+    fn fetch_count(&self, query: TableQuery) -> impl Future<Output = Result<u32, ServerFnError>> {
+        (self.count)(query)
+    }
+
+    fn fetch_export(&self, request: DownloadDataRequest) -> impl Future<Output = Result<String, ServerFnError>> {
+        (self.export)(request)
+    }
+}
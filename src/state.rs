@@ -0,0 +1,389 @@
+use leptos::*;
+use serde::{Deserialize, Serialize};
+
+use crate::model::{ColumnFilter, ColumnFilterValue, FilterExpr, SortDirection, SortSpec, TableQuery};
+
+/// The reactive state of a table (pagination, sort, search and filter) kept consistent in one place
+///
+/// The current page is derived from `offset` and `limit`, so they can never disagree.
+/// Every setter that changes the rows matching the query goes back to the first page.
+/// The state serializes as a plain object of its values, so it can be saved or sent to the server as is.
+///
+/// # Example
+///
+/// ```ignore
+/// let state = TableState::new(10);
+/// state.toggle_sort("profit_amount");
+/// state.toggle_sort_multi("name");
+/// state.set_search("john");
+/// let query: TableQuery = state.query_untracked();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TableState {
+    offset: RwSignal<u32>,
+    limit: RwSignal<u32>,
+    sort: RwSignal<Vec<SortSpec>>,
+    search: RwSignal<String>,
+    filter: RwSignal<String>,
+    filters: RwSignal<Vec<ColumnFilter>>,
+    condition: RwSignal<Option<FilterExpr>>,
+    total: RwSignal<u32>,
+}
+
+impl Default for TableState {
+    fn default() -> Self {
+        Self::new(10)
+    }
+}
+
+impl TableState {
+    /// Create a state on the first page, not sorted, without search and filter
+    pub fn new(limit: u32) -> Self {
+        Self {
+            offset: RwSignal::new(0),
+            limit: RwSignal::new(limit.max(1)),
+            sort: RwSignal::new(Vec::new()),
+            search: RwSignal::new(String::new()),
+            filter: RwSignal::new(String::new()),
+            filters: RwSignal::new(Vec::new()),
+            condition: RwSignal::new(None),
+            total: RwSignal::new(0),
+        }
+    }
+
+    /// The start of the rows to show
+    pub fn offset(&self) -> u32 {
+        self.offset.get()
+    }
+
+    /// The number of rows to show per page
+    pub fn limit(&self) -> u32 {
+        self.limit.get()
+    }
+
+    /// The sorted columns, by priority
+    pub fn sort(&self) -> Vec<SortSpec> {
+        self.sort.get()
+    }
+
+    /// The direction and the priority (starting at 1) of a column if it is sorted
+    pub fn sort_of(&self, sort_name: &str) -> Option<(SortDirection, usize)> {
+        self.sort.with(|sort| {
+            sort.iter()
+                .position(|s| s.sort_name == sort_name)
+                .map(|i| (sort[i].direction, i + 1))
+        })
+    }
+
+    pub fn search(&self) -> String {
+        self.search.get()
+    }
+
+    pub fn filter(&self) -> String {
+        self.filter.get()
+    }
+
+    /// The filters of the columns
+    pub fn filters(&self) -> Vec<ColumnFilter> {
+        self.filters.get()
+    }
+
+    /// The filter value of a column, by `sort_name`
+    pub fn filter_of(&self, field: &str) -> Option<ColumnFilterValue> {
+        self.filters.with(|filters| {
+            filters
+                .iter()
+                .find(|f| f.field == field)
+                .map(|f| f.value.clone())
+        })
+    }
+
+    /// The extra filter expression on the rows
+    pub fn condition(&self) -> Option<FilterExpr> {
+        self.condition.get()
+    }
+
+    /// The total number of rows matching the search and filter
+    pub fn total(&self) -> u32 {
+        self.total.get()
+    }
+
+    /// The current page number, starting at 1
+    pub fn page(&self) -> u32 {
+        self.offset.get() / self.limit.get() + 1
+    }
+
+    /// The number of pages, at least 1
+    pub fn page_count(&self) -> u32 {
+        self.total.get().div_ceil(self.limit.get()).max(1)
+    }
+
+    /// Go to a page, clamped between the first and the last page
+    pub fn set_page(&self, page: u32) {
+        let page = page.clamp(1, self.page_count_untracked());
+        self.offset.set((page - 1) * self.limit.get_untracked());
+    }
+
+    pub fn next_page(&self) {
+        self.set_page(self.page_untracked() + 1);
+    }
+
+    pub fn previous_page(&self) {
+        self.set_page(self.page_untracked().saturating_sub(1));
+    }
+
+    /// Change the number of rows per page and go back to the first page
+    pub fn set_page_size(&self, limit: u32) {
+        batch(|| {
+            self.limit.set(limit.max(1));
+            self.offset.set(0);
+        });
+    }
+
+    /// Sort by this column only, cycling ascending -> descending -> not sorted
+    pub fn toggle_sort(&self, sort_name: &str) {
+        let direction = match untrack(|| self.sort_of(sort_name)) {
+            Some((direction, _)) => direction.next(),
+            None => Some(SortDirection::Ascending),
+        };
+        self.set_sort(
+            direction
+                .map(|direction| vec![SortSpec::new(sort_name, direction)])
+                .unwrap_or_default(),
+        );
+    }
+
+    /// Add this column as a secondary sort key, cycling ascending -> descending -> not sorted while keeping the other columns
+    pub fn toggle_sort_multi(&self, sort_name: &str) {
+        let mut sort = self.sort.get_untracked();
+        match sort.iter().position(|s| s.sort_name == sort_name) {
+            Some(i) => match sort[i].direction.next() {
+                Some(direction) => sort[i].direction = direction,
+                None => {
+                    sort.remove(i);
+                }
+            },
+            None => sort.push(SortSpec::new(sort_name, SortDirection::Ascending)),
+        }
+        self.set_sort(sort);
+    }
+
+    /// Replace the sorted columns and go back to the first page
+    pub fn set_sort(&self, sort: Vec<SortSpec>) {
+        batch(|| {
+            self.sort.set(sort);
+            self.offset.set(0);
+        });
+    }
+
+    /// Change the search keyword and go back to the first page
+    pub fn set_search(&self, search: &str) {
+        if self.search.get_untracked() == search {
+            return;
+        }
+        batch(|| {
+            self.search.set(search.to_string());
+            self.offset.set(0);
+        });
+    }
+
+    /// Change the filter and go back to the first page
+    pub fn set_filter(&self, filter: &str) {
+        if self.filter.get_untracked() == filter {
+            return;
+        }
+        batch(|| {
+            self.filter.set(filter.to_string());
+            self.offset.set(0);
+        });
+    }
+
+    /// Set (or clear with `None`) the filter of a column and go back to the first page
+    pub fn set_column_filter(&self, field: &str, value: Option<ColumnFilterValue>) {
+        let mut filters = self.filters.get_untracked();
+        let position = filters.iter().position(|f| f.field == field);
+        match (position, value) {
+            (Some(i), Some(value)) if filters[i].value == value => return,
+            (Some(i), Some(value)) => filters[i].value = value,
+            (None, Some(value)) => filters.push(ColumnFilter { field: field.to_string(), value }),
+            (Some(i), None) => {
+                filters.remove(i);
+            }
+            (None, None) => return,
+        }
+        batch(|| {
+            self.filters.set(filters);
+            self.offset.set(0);
+        });
+    }
+
+    /// Clear the filters of every column and go back to the first page
+    pub fn clear_column_filters(&self) {
+        batch(|| {
+            self.filters.set(Vec::new());
+            self.offset.set(0);
+        });
+    }
+
+    /// Change the extra filter expression on the rows and go back to the first page
+    pub fn set_condition(&self, condition: Option<FilterExpr>) {
+        if self.condition.with_untracked(|c| *c == condition) {
+            return;
+        }
+        batch(|| {
+            self.condition.set(condition);
+            self.offset.set(0);
+        });
+    }
+
+    /// Update the total number of rows, moving back to the last page if the current one no longer exists
+    pub fn set_total(&self, total: u32) {
+        if self.total.get_untracked() == total {
+            return;
+        }
+        batch(|| {
+            self.total.set(total);
+            if self.offset.get_untracked() >= total {
+                let limit = self.limit.get_untracked();
+                self.offset.set((self.page_count_untracked() - 1) * limit);
+            }
+        });
+    }
+
+    /// The query of the current page, tracked
+    pub fn query(&self) -> TableQuery {
+        TableQuery {
+            offset: self.offset.get(),
+            limit: self.limit.get(),
+            sort: self.sort.get(),
+            search: self.search.get(),
+            filter: self.filter.get(),
+            filters: self.filters.get(),
+            condition: self.condition.get(),
+        }
+    }
+
+    /// The query of the current page, untracked
+    pub fn query_untracked(&self) -> TableQuery {
+        untrack(|| self.query())
+    }
+
+    fn page_untracked(&self) -> u32 {
+        untrack(|| self.page())
+    }
+
+    fn page_count_untracked(&self) -> u32 {
+        untrack(|| self.page_count())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_page_is_clamped() {
+        let runtime = create_runtime();
+        let state = TableState::new(10);
+        state.set_total(35);
+        state.set_page(3);
+        assert_eq!((state.offset(), state.page(), state.page_count()), (20, 3, 4));
+        state.set_page(9);
+        assert_eq!((state.offset(), state.page()), (30, 4));
+        state.set_page(0);
+        assert_eq!((state.offset(), state.page()), (0, 1));
+        state.next_page();
+        state.next_page();
+        state.previous_page();
+        assert_eq!(state.page(), 2);
+        runtime.dispose();
+    }
+
+    #[test]
+    fn page_size_and_search_reset_the_offset() {
+        let runtime = create_runtime();
+        let state = TableState::new(10);
+        state.set_total(100);
+        state.set_page(5);
+        state.set_page_size(25);
+        assert_eq!((state.offset(), state.limit(), state.page_count()), (0, 25, 4));
+        state.set_page_size(0);
+        assert_eq!(state.limit(), 1);
+        state.set_page_size(10);
+        state.set_page(4);
+        state.set_search("john");
+        assert_eq!((state.search(), state.offset()), ("john".to_string(), 0));
+        state.set_page(4);
+        state.set_search("john");
+        assert_eq!(state.offset(), 30);
+        runtime.dispose();
+    }
+
+    #[test]
+    fn set_total_moves_back_to_the_last_page() {
+        let runtime = create_runtime();
+        let state = TableState::new(10);
+        state.set_total(50);
+        state.set_page(5);
+        state.set_total(45);
+        assert_eq!(state.page(), 5);
+        state.set_total(25);
+        assert_eq!((state.offset(), state.page(), state.page_count()), (20, 3, 3));
+        state.set_total(0);
+        assert_eq!((state.offset(), state.page(), state.page_count()), (0, 1, 1));
+        runtime.dispose();
+    }
+
+    #[test]
+    fn page_is_derived_from_offset_and_limit() {
+        let runtime = create_runtime();
+        let state = TableState::new(20);
+        assert_eq!((state.page(), state.page_count()), (1, 1));
+        state.set_total(41);
+        assert_eq!(state.page_count(), 3);
+        state.set_page(2);
+        assert_eq!((state.offset(), state.page()), (20, 2));
+        assert_eq!((state.query_untracked().offset, state.query_untracked().limit), (20, 20));
+        runtime.dispose();
+    }
+
+    #[test]
+    fn toggle_sort_cycles_one_column() {
+        let runtime = create_runtime();
+        let state = TableState::new(10);
+        state.toggle_sort_multi("name");
+        state.toggle_sort("profit");
+        assert_eq!(state.sort(), vec![SortSpec::new("profit", SortDirection::Ascending)]);
+        state.toggle_sort("profit");
+        assert_eq!(state.sort_of("profit"), Some((SortDirection::Descending, 1)));
+        state.toggle_sort("profit");
+        assert!(state.sort().is_empty());
+        state.set_total(100);
+        state.set_page(3);
+        state.toggle_sort("name");
+        assert_eq!(state.offset(), 0);
+        runtime.dispose();
+    }
+
+    #[test]
+    fn toggle_sort_multi_keeps_the_priorities() {
+        let runtime = create_runtime();
+        let state = TableState::new(10);
+        state.toggle_sort_multi("name");
+        state.toggle_sort_multi("profit");
+        state.toggle_sort_multi("date");
+        assert_eq!(state.sort_of("name"), Some((SortDirection::Ascending, 1)));
+        assert_eq!(state.sort_of("profit"), Some((SortDirection::Ascending, 2)));
+        assert_eq!(state.sort_of("date"), Some((SortDirection::Ascending, 3)));
+        state.toggle_sort_multi("profit");
+        assert_eq!(state.sort_of("profit"), Some((SortDirection::Descending, 2)));
+        state.toggle_sort_multi("profit");
+        assert_eq!(state.sort_of("profit"), None);
+        assert_eq!(state.sort_of("date"), Some((SortDirection::Ascending, 2)));
+        assert_eq!(
+            state.sort(),
+            vec![SortSpec::new("name", SortDirection::Ascending), SortSpec::new("date", SortDirection::Ascending)]
+        );
+        runtime.dispose();
+    }
+}
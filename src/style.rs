@@ -0,0 +1,240 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+use regex_lite::Regex;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+
+use crate::model::{compare_json, json_to_text, resolve_path};
+
+/// A comparison of a value against the value of another column
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl CompareOp {
+    fn holds(&self, ordering: Ordering) -> bool {
+        match self {
+            CompareOp::Eq => ordering == Ordering::Equal,
+            CompareOp::Ne => ordering != Ordering::Equal,
+            CompareOp::Lt => ordering == Ordering::Less,
+            CompareOp::Le => ordering != Ordering::Greater,
+            CompareOp::Gt => ordering == Ordering::Greater,
+            CompareOp::Ge => ordering != Ordering::Less,
+        }
+    }
+}
+
+/// A compiled regular expression, serialized as its pattern
+#[derive(Clone)]
+pub struct Pattern(Regex);
+
+impl Pattern {
+    pub fn new(pattern: &str) -> Result<Self, regex_lite::Error> {
+        Regex::new(pattern).map(Pattern)
+    }
+
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+
+    pub fn is_match(&self, text: &str) -> bool {
+        self.0.is_match(text)
+    }
+}
+
+impl fmt::Debug for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Pattern").field(&self.as_str()).finish()
+    }
+}
+
+impl PartialEq for Pattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Serialize for Pattern {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Pattern {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let pattern = String::deserialize(deserializer)?;
+        Pattern::new(&pattern).map_err(serde::de::Error::custom)
+    }
+}
+
+/// The condition of a `StyleRule`, checked against the text of a value
+///
+/// Numeric conditions only match values that parse as numbers.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum StyleCondition {
+    /// The value is exactly this text
+    Equals(String),
+    /// The value is this text, ignoring the case
+    EqualsIgnoreCase(String),
+    /// The value contains this text, ignoring the case (the matching of `style_when_success` and `style_when_error`)
+    Contains(String),
+    /// The value matches this regular expression
+    Regex(Pattern),
+    /// The value is a number above this threshold
+    Above(f64),
+    /// The value is a number below this threshold
+    Below(f64),
+    /// The value is a number at least this threshold
+    AtLeast(f64),
+    /// The value is a number at most this threshold
+    AtMost(f64),
+    /// The value is a number in the inclusive range
+    Between { min: f64, max: f64 },
+    /// The value compared with the value of another column (as numbers when both are numbers, as text otherwise)
+    Column { op: CompareOp, field: String },
+    /// The value is empty
+    Empty,
+}
+
+impl StyleCondition {
+    /// A regular expression condition, the pattern is compiled once
+    pub fn regex(pattern: &str) -> Result<Self, regex_lite::Error> {
+        Pattern::new(pattern).map(StyleCondition::Regex)
+    }
+
+    /// Check the condition
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The text of the value
+    /// * `row` - The row of the value, for the comparisons with other columns
+    pub fn matches(&self, value: &str, row: &Value) -> bool {
+        let number = value.trim().parse::<f64>().ok();
+        match self {
+            StyleCondition::Equals(text) => value == text,
+            StyleCondition::EqualsIgnoreCase(text) => value.to_lowercase() == text.to_lowercase(),
+            StyleCondition::Contains(text) => !text.is_empty() && value.to_uppercase().contains(&text.to_uppercase()),
+            StyleCondition::Regex(pattern) => pattern.is_match(value),
+            StyleCondition::Above(threshold) => number.is_some_and(|n| n > *threshold),
+            StyleCondition::Below(threshold) => number.is_some_and(|n| n < *threshold),
+            StyleCondition::AtLeast(threshold) => number.is_some_and(|n| n >= *threshold),
+            StyleCondition::AtMost(threshold) => number.is_some_and(|n| n <= *threshold),
+            StyleCondition::Between { min, max } => number.is_some_and(|n| n >= *min && n <= *max),
+            StyleCondition::Column { op, field } => resolve_path(row, field)
+                .and_then(|other| compare_json(&Value::from(value), other))
+                .is_some_and(|ordering| op.holds(ordering)),
+            StyleCondition::Empty => value.trim().is_empty(),
+        }
+    }
+}
+
+/// A condition mapped to CSS classes
+///
+/// # Arguments
+///
+/// * `field` - Optional field (or nested path) of the row to check, the value of the column is checked when none is given (required for row rules)
+/// * `when` - The condition
+/// * `class` - The classes added when the condition matches
+/// * `stop` - A boolean to skip the next rules when this rule matches
+///
+/// # Example
+///
+/// ```ignore
+/// let rules = vec![
+///     StyleRule::new(StyleCondition::Above(1000.0), "text-error font-bold").stop(),
+///     StyleRule::new(StyleCondition::Above(100.0), "text-warning"),
+///     StyleRule::new(StyleCondition::Equals("failed".to_string()), "bg-error/20").on("status"),
+/// ];
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct StyleRule {
+    #[serde(default)]
+    pub field: Option<String>,
+    pub when: StyleCondition,
+    pub class: String,
+    #[serde(default)]
+    pub stop: bool,
+}
+
+impl StyleRule {
+    pub fn new(when: StyleCondition, class: &str) -> Self {
+        Self {
+            field: None,
+            when,
+            class: class.to_string(),
+            stop: false,
+        }
+    }
+
+    /// Check this field of the row instead of the value of the column
+    pub fn on(mut self, field: &str) -> Self {
+        self.field = Some(field.to_string());
+        self
+    }
+
+    /// Skip the next rules when this rule matches
+    pub fn stop(mut self) -> Self {
+        self.stop = true;
+        self
+    }
+
+    /// Check the rule, `value` is used when the rule has no field
+    pub fn matches(&self, value: Option<&str>, row: &Value) -> bool {
+        match &self.field {
+            Some(field) => {
+                let text = resolve_path(row, field).filter(|value| !value.is_null()).map(json_to_text).unwrap_or_default();
+                self.when.matches(&text, row)
+            }
+            None => value.is_some_and(|value| self.when.matches(value, row)),
+        }
+    }
+}
+
+/// The classes of the matching rules in order, until a matching rule with `stop`
+///
+/// # Arguments
+///
+/// * `rules` - The ordered rules
+/// * `value` - The text of the value of the column, `None` for row rules
+/// * `row` - The row
+pub fn rule_classes(rules: &[StyleRule], value: Option<&str>, row: &Value) -> String {
+    let mut classes: Vec<&str> = Vec::new();
+    for rule in rules {
+        if rule.matches(value, row) {
+            classes.push(&rule.class);
+            if rule.stop {
+                break;
+            }
+        }
+    }
+    classes.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn conditions() {
+        let row = json!({"price": 10, "target": "9.5", "status": "failed", "note": null});
+        let column = |op| StyleCondition::Column { op, field: "target".to_string() };
+        assert!(column(CompareOp::Gt).matches("10", &row));
+        assert!(!column(CompareOp::Lt).matches("10", &row));
+        assert!(!StyleCondition::Column { op: CompareOp::Eq, field: "note".to_string() }.matches("", &row));
+        assert!(StyleCondition::regex("^(Loss|Failed)$").unwrap().matches("Failed", &row));
+        assert!(StyleCondition::regex("(").is_err());
+        assert!(StyleRule::new(StyleCondition::Equals("failed".to_string()), "bg-error").on("status").matches(None, &row));
+        assert!(StyleRule::new(StyleCondition::Empty, "italic").on("note").matches(None, &row));
+    }
+}
@@ -0,0 +1,173 @@
+use serde::{Deserialize, Serialize};
+
+/// The classes of the parts of a table
+///
+/// A theme is given to `DataTable` with the `theme` prop, or provided to every table below a component with `provide_context`.
+/// The daisyUI theme (`TableTheme::daisy_ui`) is used when none is given, `TableTheme::tailwind` only uses plain Tailwind classes.
+/// A theme deserialized without some of the parts takes them from the daisyUI theme.
+///
+/// # Arguments
+///
+/// * `table` - The `<table>`
+/// * `header_cell` - A header cell `<th>`
+/// * `filter_cell` - A cell `<th>` of the filter row
+/// * `row` - Every row `<tr>`
+/// * `alt_row` - Added to every other row `<tr>`
+/// * `cell` - A cell `<td>`
+/// * `pagination_button` - The previous and next buttons
+/// * `page_button` - The buttons of the page numbers
+/// * `search_input` - The search input, also used by the text inputs of the filter row
+/// * `select` - The page size select, also used by the selects of the filter row
+/// * `download_button` - The download button
+/// * `mobile_label` - The label of a value in the mobile view
+/// * `mobile_toggle` - The button expanding a row in the mobile view
+/// * `checkbox` - The checkboxes of the selection column
+/// * `radio` - The radios of the selection column in single mode
+/// * `spinner` - The spinner shown while loading
+/// * `success_text` - The non negative numbers and the success values of the legacy styling (`is_number_styled`, `style_when_success`)
+/// * `error_text` - The error messages, the destructive bulk actions and the negative numbers and error values of the legacy styling
+/// * `banner` - The banner to select the rows of every page
+/// * `selection_badge` - The number of selected rows
+/// * `bulk_button` - The buttons of the bulk actions
+/// * `modal` - The backdrop of the confirmation dialog, only rendered while open
+/// * `modal_box` - The box of the confirmation dialog
+/// * `modal_action` - The buttons row of the confirmation dialog
+/// * `cancel_button` - The cancel button of the confirmation dialog
+/// * `confirm_button` - The confirm button of the confirmation dialog
+/// * `link_cell` - A cell of `CellRenderer::Link`
+/// * `badge_cell` - A cell of `CellRenderer::Badge`, before its own classes
+/// * `button_cell` - A cell of `CellRenderer::Button`
+/// * `chip` - An item of an array displayed as chips
+///
+/// # Example
+///
+/// ```ignore
+/// provide_context(TableTheme { header_cell: "cursor-pointer uppercase bg-brand text-white".to_string(), ..TableTheme::daisy_ui() });
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct TableTheme {
+    pub table: String,
+    pub header_cell: String,
+    pub filter_cell: String,
+    pub row: String,
+    pub alt_row: String,
+    pub cell: String,
+    pub pagination_button: String,
+    pub page_button: String,
+    pub search_input: String,
+    pub select: String,
+    pub download_button: String,
+    pub mobile_label: String,
+    pub mobile_toggle: String,
+    pub checkbox: String,
+    pub radio: String,
+    pub spinner: String,
+    pub success_text: String,
+    pub error_text: String,
+    pub banner: String,
+    pub selection_badge: String,
+    pub bulk_button: String,
+    pub modal: String,
+    pub modal_box: String,
+    pub modal_action: String,
+    pub cancel_button: String,
+    pub confirm_button: String,
+    pub link_cell: String,
+    pub badge_cell: String,
+    pub button_cell: String,
+    pub chip: String,
+}
+
+impl Default for TableTheme {
+    fn default() -> Self {
+        Self::daisy_ui()
+    }
+}
+
+impl TableTheme {
+    /// The daisyUI classes the table has always used
+    pub fn daisy_ui() -> Self {
+        Self {
+            table: "table table-xs table-zebra-zebra mt-1".to_string(),
+            header_cell: "cursor-pointer text-sm text-white bg-opacity-50 bg-success uppercase select-none".to_string(),
+            filter_cell: "bg-base-200 font-normal".to_string(),
+            row: "uppercase hover:opacity-50".to_string(),
+            alt_row: "bg-base-300".to_string(),
+            cell: String::new(),
+            pagination_button: "btn btn-ghost btn-xs".to_string(),
+            page_button: "btn btn-square btn-xs".to_string(),
+            search_input: "input input-xs rounded input-info focus:outline-none focus:shadow-outline".to_string(),
+            select: "text-xs border-gray-800 rounded shadow-md select-xs hover:shadow-sm hover:shadow-success bg-base-100".to_string(),
+            download_button: "font-normal btn btn-xs btn-ghost bg-base-100 rounded text-xs".to_string(),
+            mobile_label: "mr-2 text-sm text-success".to_string(),
+            mobile_toggle: "flex justify-start w-full gap-2 border-l-2 rounded-none btn btn-ghost btn-md bg-base-100 border-l-success".to_string(),
+            checkbox: "checkbox checkbox-xs".to_string(),
+            radio: "radio radio-xs".to_string(),
+            spinner: "loading loading-spinner loading-xs".to_string(),
+            success_text: "text-success".to_string(),
+            error_text: "text-error".to_string(),
+            banner: "text-xs bg-base-200 rounded p-1".to_string(),
+            selection_badge: "badge badge-sm badge-success".to_string(),
+            bulk_button: "font-normal btn btn-xs btn-ghost bg-base-100 rounded text-xs".to_string(),
+            modal: "modal modal-open".to_string(),
+            modal_box: "modal-box".to_string(),
+            modal_action: "modal-action".to_string(),
+            cancel_button: "btn btn-sm btn-ghost".to_string(),
+            confirm_button: "btn btn-sm btn-error".to_string(),
+            link_cell: "link link-hover".to_string(),
+            badge_cell: "badge badge-sm".to_string(),
+            button_cell: "btn btn-xs btn-ghost".to_string(),
+            chip: "badge badge-sm badge-ghost mr-1".to_string(),
+        }
+    }
+
+    /// Plain Tailwind classes, without daisyUI
+    pub fn tailwind() -> Self {
+        Self {
+            table: "w-full mt-1 text-xs border-collapse".to_string(),
+            header_cell: "cursor-pointer px-2 py-1 text-left text-sm font-semibold text-gray-700 uppercase bg-gray-100 select-none".to_string(),
+            filter_cell: "px-2 py-1 font-normal bg-gray-50".to_string(),
+            row: "uppercase hover:bg-gray-50".to_string(),
+            alt_row: "bg-gray-100".to_string(),
+            cell: "px-2 py-1".to_string(),
+            pagination_button: "px-2 py-1 text-xs rounded hover:bg-gray-100 disabled:opacity-50".to_string(),
+            page_button: "w-6 h-6 text-xs rounded hover:bg-gray-100 disabled:bg-gray-200".to_string(),
+            search_input: "px-2 py-1 text-xs border border-gray-300 rounded focus:outline-none focus:ring-1 focus:ring-blue-500".to_string(),
+            select: "px-1 py-1 text-xs bg-white border border-gray-300 rounded".to_string(),
+            download_button: "px-2 py-1 text-xs bg-white border border-gray-300 rounded hover:bg-gray-100".to_string(),
+            mobile_label: "mr-2 text-sm text-gray-500".to_string(),
+            mobile_toggle: "flex justify-start w-full gap-2 px-2 py-2 bg-white border-l-2 border-l-blue-500".to_string(),
+            checkbox: "w-3 h-3 rounded border-gray-300 accent-blue-600".to_string(),
+            radio: "w-3 h-3 border-gray-300 accent-blue-600".to_string(),
+            spinner: "inline-block w-4 h-4 border-2 border-gray-300 border-t-blue-500 rounded-full animate-spin".to_string(),
+            success_text: "text-green-600".to_string(),
+            error_text: "text-red-600".to_string(),
+            banner: "text-xs bg-gray-100 rounded p-1".to_string(),
+            selection_badge: "px-2 py-0.5 text-xs text-white bg-green-600 rounded-full".to_string(),
+            bulk_button: "px-2 py-1 text-xs bg-white border border-gray-300 rounded hover:bg-gray-100".to_string(),
+            modal: "fixed inset-0 z-50 flex items-center justify-center bg-black/40".to_string(),
+            modal_box: "w-full max-w-md p-4 bg-white rounded shadow-lg".to_string(),
+            modal_action: "flex justify-end gap-2 mt-4".to_string(),
+            cancel_button: "px-3 py-1 text-sm rounded hover:bg-gray-100".to_string(),
+            confirm_button: "px-3 py-1 text-sm text-white bg-red-600 rounded hover:bg-red-700".to_string(),
+            link_cell: "text-blue-600 hover:underline".to_string(),
+            badge_cell: "inline-block px-2 py-0.5 text-xs rounded-full bg-gray-100".to_string(),
+            button_cell: "px-2 py-0.5 text-xs rounded hover:bg-gray-100".to_string(),
+            chip: "inline-block px-2 py-0.5 mr-1 text-xs rounded-full bg-gray-100".to_string(),
+        }
+    }
+
+    /// The theme provided in the context, the daisyUI theme when none is provided
+    pub fn from_context() -> Self {
+        leptos::use_context::<TableTheme>().unwrap_or_default()
+    }
+
+    /// The classes of a row by its index, `alt_row` is added to every other row
+    pub fn row_class(&self, index: usize) -> String {
+        match index % 2 == 0 {
+            true => self.row.clone(),
+            false => format!("{} {}", self.row, self.alt_row),
+        }
+    }
+}